@@ -0,0 +1,51 @@
+#![cfg_attr(feature = "blocking", allow(clippy::result_large_err))]
+use crate::DropboxResult;
+use std::{
+    fs,
+    io::{self, Read, Seek},
+    path::{Component, Path, PathBuf},
+};
+use zip::{result::ZipError, ZipArchive};
+
+///unpack a zip archive (e.g. from /download_zip) into `dest`
+///entries which would escape `dest` are rejected with an error instead of being written
+///return the paths of the extracted files
+pub fn extract_zip<R: Read + Seek>(reader: R, dest: &Path) -> DropboxResult<Vec<PathBuf>> {
+    let mut archive = ZipArchive::new(reader)?;
+    let mut extracted = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let relative = sanitize_entry_path(entry.name())
+            .ok_or(ZipError::InvalidArchive("unsafe entry path"))?;
+        let out_path = dest.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path).map_err(ZipError::Io)?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(ZipError::Io)?;
+        }
+        let mut out_file = fs::File::create(&out_path).map_err(ZipError::Io)?;
+        io::copy(&mut entry, &mut out_file).map_err(ZipError::Io)?;
+        extracted.push(out_path);
+    }
+    Ok(extracted)
+}
+
+///turn an entry name into a relative path which stays inside the extract directory
+///return `None` for absolute paths, `..` components or empty names
+pub fn sanitize_entry_path(name: &str) -> Option<PathBuf> {
+    let name = name.replace('\\', "/");
+    let mut path = PathBuf::new();
+    for component in Path::new(&name).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    if path.as_os_str().is_empty() {
+        return None;
+    }
+    Some(path)
+}
@@ -0,0 +1,461 @@
+#![allow(clippy::result_large_err)]
+use clap::{Args, Parser, Subcommand};
+use rust_dropbox::{
+    client::DBXClient, files::Metadata, DropboxResult, ListFolderOptionBuilder,
+    MetadataOptionBuilder, MoveCopyOption, MoveCopyOptionBuilder, UploadMode, UploadOptionBuilder,
+};
+use serde::Serialize;
+use std::{env, fs, io, path::PathBuf, process};
+
+///command line client for the dropbox api
+#[derive(Parser)]
+#[command(name = "dbx", version)]
+struct Cli {
+    ///access token, overrides the token of the config file
+    #[arg(long, env = "DROPBOX_TOKEN", hide_env_values = true)]
+    token: Option<String>,
+    ///config file with `{"token": "..."}`, defaults to $XDG_CONFIG_HOME/dbx/config.json
+    #[arg(long)]
+    config: Option<PathBuf>,
+    ///print results as json
+    #[arg(long)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    ///list a folder
+    Ls {
+        #[arg(default_value = "")]
+        path: String,
+        #[arg(short, long)]
+        recursive: bool,
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    ///upload a local file
+    Put {
+        local: PathBuf,
+        remote: String,
+        ///overwrite the remote file
+        #[arg(long, conflicts_with = "update")]
+        overwrite: bool,
+        ///only replace the remote file if it is still at this revision
+        #[arg(long, value_name = "REV")]
+        update: Option<String>,
+        ///fail instead of renaming the upload on a conflict
+        #[arg(long)]
+        no_autorename: bool,
+        ///do not notify the user's devices about the upload
+        #[arg(long)]
+        mute: bool,
+        ///also treat uploading identical content as a conflict
+        #[arg(long)]
+        strict_conflict: bool,
+    },
+    ///download a file, `-` writes to stdout
+    Get {
+        remote: String,
+        ///defaults to the file name in the current directory
+        local: Option<PathBuf>,
+    },
+    ///move a file or folder
+    Mv {
+        from: String,
+        to: String,
+        #[command(flatten)]
+        option: MoveCopyArgs,
+    },
+    ///copy a file or folder
+    Cp {
+        from: String,
+        to: String,
+        #[command(flatten)]
+        option: MoveCopyArgs,
+    },
+    ///delete a file or folder
+    Rm {
+        path: String,
+        ///only delete the file if it is still at this revision
+        #[arg(long, value_name = "REV")]
+        rev: Option<String>,
+    },
+    ///create a folder
+    Mkdir {
+        path: String,
+        #[arg(long)]
+        autorename: bool,
+    },
+    ///show the metadata of a file or folder
+    Stat {
+        path: String,
+        #[arg(long)]
+        include_deleted: bool,
+    },
+    ///create a shared link
+    Share { path: String },
+}
+
+#[derive(Args)]
+struct MoveCopyArgs {
+    ///allow moving or copying shared folders
+    #[arg(long)]
+    allow_shared_folder: bool,
+    ///rename the target instead of failing on a conflict
+    #[arg(long)]
+    autorename: bool,
+    ///allow moves which change the owner of the content
+    #[arg(long)]
+    allow_ownership_transfer: bool,
+}
+
+impl MoveCopyArgs {
+    fn build(&self) -> MoveCopyOption {
+        let mut builder = MoveCopyOptionBuilder::new();
+        if self.allow_shared_folder {
+            builder.allow_shared_folder();
+        }
+        if self.autorename {
+            builder.allow_auto_rename();
+        }
+        if self.allow_ownership_transfer {
+            builder.allow_ownership_transfer();
+        }
+        builder.build()
+    }
+}
+
+#[derive(Serialize)]
+struct Transfer<'a> {
+    from_path: &'a str,
+    to_path: &'a str,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let token = match cli
+        .token
+        .clone()
+        .or_else(|| config_token(cli.config.clone()))
+    {
+        Some(token) => token,
+        None => {
+            eprintln!("dbx: no token, set DROPBOX_TOKEN, pass --token or add it to the config");
+            process::exit(2);
+        }
+    };
+    let client = DBXClient::new(&token);
+    if let Err(e) = run(&client, &cli) {
+        eprintln!("dbx: {:?}", e);
+        process::exit(1);
+    }
+}
+
+fn run(client: &DBXClient, cli: &Cli) -> DropboxResult<()> {
+    match &cli.command {
+        Command::Ls {
+            path,
+            recursive,
+            include_deleted,
+        } => {
+            let mut builder = ListFolderOptionBuilder::new();
+            if *recursive {
+                builder.recursive();
+            }
+            if *include_deleted {
+                builder.include_deleted();
+            }
+            let mut result = client.list_folder(path, builder.build())?;
+            let mut entries = result.entries;
+            while result.has_more {
+                result = client.list_folder_continue(&result.cursor)?;
+                entries.append(&mut result.entries);
+            }
+            if cli.json {
+                return print_json(&entries);
+            }
+            for entry in &entries {
+                println!("{}", metadata_line(entry));
+            }
+        }
+        Command::Put {
+            local,
+            remote,
+            overwrite,
+            update,
+            no_autorename,
+            mute,
+            strict_conflict,
+        } => {
+            let mut builder = UploadOptionBuilder::new();
+            if *overwrite {
+                builder.set_upload_mode(UploadMode::Overwrite);
+            }
+            if let Some(rev) = update {
+                builder.set_upload_mode(UploadMode::Update(rev.clone()));
+            }
+            if *no_autorename {
+                builder.disallow_auto_rename();
+            }
+            if *mute {
+                builder.mute_notification();
+            }
+            if *strict_conflict {
+                builder.allow_strict_conflict();
+            }
+            let metadata = client.upload_file(fs::read(local)?, remote, builder.build())?;
+            if cli.json {
+                return print_json(&metadata);
+            }
+            println!("{}", metadata_line(&Metadata::File(metadata)));
+        }
+        Command::Get { remote, local } => {
+            let mut stream = client.download_stream(remote)?;
+            match local {
+                Some(path) if path.as_os_str() == "-" => {
+                    io::copy(&mut stream.reader, &mut io::stdout().lock())?;
+                }
+                //the name of the metadata also works for `id:` and `rev:` paths
+                path => {
+                    let path = path
+                        .clone()
+                        .unwrap_or_else(|| PathBuf::from(&stream.metadata.name));
+                    io::copy(&mut stream.reader, &mut fs::File::create(path)?)?;
+                }
+            }
+        }
+        Command::Mv { from, to, option } => {
+            client.move_file(from, to, option.build())?;
+            if cli.json {
+                return print_json(&Transfer {
+                    from_path: from,
+                    to_path: to,
+                });
+            }
+        }
+        Command::Cp { from, to, option } => {
+            client.copy(from, to, option.build())?;
+            if cli.json {
+                return print_json(&Transfer {
+                    from_path: from,
+                    to_path: to,
+                });
+            }
+        }
+        Command::Rm { path, rev } => {
+            let metadata = client.delete(path, rev.as_deref())?;
+            if cli.json {
+                return print_json(&metadata);
+            }
+        }
+        Command::Mkdir { path, autorename } => {
+            let metadata = client.create_folder(path, *autorename)?;
+            if cli.json {
+                return print_json(&metadata);
+            }
+        }
+        Command::Stat {
+            path,
+            include_deleted,
+        } => {
+            let mut builder = MetadataOptionBuilder::new();
+            if *include_deleted {
+                builder.include_deleted();
+            }
+            let metadata = client.get_metadata(path, builder.build())?;
+            if cli.json {
+                return print_json(&metadata);
+            }
+            print_stat(&metadata);
+        }
+        Command::Share { path } => {
+            let link = client.create_shared_link(path)?;
+            if cli.json {
+                return print_json(&link);
+            }
+            println!("{}", link.url);
+        }
+    }
+    Ok(())
+}
+
+///token from the config file, `None` if there is no readable config
+fn config_token(path: Option<PathBuf>) -> Option<String> {
+    let path = path.or_else(|| {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(dir.join("dbx").join("config.json"))
+    })?;
+    let config: serde_json::Value = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+    config["token"].as_str().map(|token| token.to_string())
+}
+
+fn print_json<T: Serialize>(value: &T) -> DropboxResult<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn metadata_line(metadata: &Metadata) -> String {
+    match metadata {
+        Metadata::File(file) => format!(
+            "{:>12}  {}",
+            file.size,
+            file.path_display.as_deref().unwrap_or(&file.name)
+        ),
+        Metadata::Folder(folder) => format!(
+            "{:>12}  {}/",
+            "-",
+            folder.path_display.as_deref().unwrap_or(&folder.name)
+        ),
+        Metadata::Deleted(deleted) => format!(
+            "{:>12}  {} (deleted)",
+            "-",
+            deleted.path_display.as_deref().unwrap_or(&deleted.name)
+        ),
+    }
+}
+
+fn print_stat(metadata: &Metadata) {
+    match metadata {
+        Metadata::File(file) => {
+            println!(
+                "path: {}",
+                file.path_display.as_deref().unwrap_or(&file.name)
+            );
+            println!("id: {}", file.id);
+            println!("rev: {}", file.rev);
+            println!("size: {}", file.size);
+            println!("server_modified: {}", file.server_modified);
+            if let Some(ref hash) = file.content_hash {
+                println!("content_hash: {}", hash);
+            }
+        }
+        Metadata::Folder(folder) => {
+            println!(
+                "path: {}",
+                folder.path_display.as_deref().unwrap_or(&folder.name)
+            );
+            println!("id: {}", folder.id);
+        }
+        Metadata::Deleted(deleted) => {
+            println!(
+                "path: {} (deleted)",
+                deleted.path_display.as_deref().unwrap_or(&deleted.name)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(["dbx", "--token", "token"].iter().chain(args))
+    }
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_ls() {
+        let cli = parse(&["--json", "ls"]).unwrap();
+        assert!(cli.json);
+        assert!(matches!(
+            cli.command,
+            Command::Ls { ref path, recursive: false, include_deleted: false } if path.is_empty()
+        ));
+        let cli = parse(&["ls", "/test", "-r"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Ls { ref path, recursive: true, .. } if path == "/test"
+        ));
+    }
+
+    #[test]
+    fn test_parse_put() {
+        let cli = parse(&[
+            "put",
+            "a.txt",
+            "/test/a.txt",
+            "--update",
+            "a1c10ce0dd78",
+            "--mute",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Put {
+                local,
+                remote,
+                overwrite,
+                update,
+                mute,
+                ..
+            } => {
+                assert_eq!(local, PathBuf::from("a.txt"));
+                assert_eq!(remote, "/test/a.txt");
+                assert!(!overwrite);
+                assert_eq!(update.as_deref(), Some("a1c10ce0dd78"));
+                assert!(mute);
+            }
+            _ => panic!("not a put command"),
+        }
+        let err = parse(&[
+            "put",
+            "a.txt",
+            "/test/a.txt",
+            "--overwrite",
+            "--update",
+            "rev",
+        ]);
+        assert_eq!(
+            err.err().unwrap().kind(),
+            clap::error::ErrorKind::ArgumentConflict
+        );
+        let err = parse(&["put", "a.txt"]);
+        assert_eq!(
+            err.err().unwrap().kind(),
+            clap::error::ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn test_parse_get() {
+        let cli = parse(&["get", "id:a4ayc_80_OEAAAAAAAAAXw"]).unwrap();
+        assert!(matches!(cli.command, Command::Get { local: None, .. }));
+        let cli = parse(&["get", "/test/a.txt", "-"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Get { local: Some(ref local), .. } if local.as_os_str() == "-"
+        ));
+    }
+
+    #[test]
+    fn test_parse_move_copy() {
+        let cli = parse(&["mv", "/a", "/b", "--autorename", "--allow-shared-folder"]).unwrap();
+        match cli.command {
+            Command::Mv { from, to, option } => {
+                assert_eq!((from.as_str(), to.as_str()), ("/a", "/b"));
+                assert!(option.autorename && option.allow_shared_folder);
+                assert!(!option.allow_ownership_transfer);
+            }
+            _ => panic!("not a mv command"),
+        }
+        assert!(parse(&["cp", "/a"]).is_err());
+        assert!(parse(&["rm", "/a", "--rev", "a1c10ce0dd78"]).is_ok());
+        assert!(parse(&["frobnicate"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let cli = Cli::try_parse_from(["dbx", "--config", "dbx.json", "share", "/a"]).unwrap();
+        assert_eq!(cli.config, Some(PathBuf::from("dbx.json")));
+        assert!(matches!(cli.command, Command::Share { ref path } if path == "/a"));
+    }
+}
@@ -1,23 +1,265 @@
+#![cfg_attr(feature = "blocking", allow(clippy::result_large_err))]
+#[cfg(feature = "non-blocking")]
+use crate::io_error;
+#[cfg(feature = "metrics")]
+use crate::metric;
+#[cfg(feature = "tracing")]
+use crate::trace;
 use crate::{
-    DbxRequestErrorSummary, DbxRequestLimitsErrorSummary, DropboxError, DropboxResult,
-    MoveCopyOption, UploadMode, UploadOption,
+    content_hash::ContentHasher,
+    file_properties::{
+        ListTemplateResult, PropertiesSearchQuery, PropertiesSearchResult, PropertyFieldTemplate,
+        PropertyGroup, PropertyGroupTemplate, PropertyGroupUpdate, TemplateIdResult,
+    },
+    file_requests::{
+        CountFileRequestsResult, DeleteFileRequestsResult, FileRequest, FileRequestOption,
+        FileRequestUpdate, ListFileRequestsResult,
+    },
+    files::{
+        lock_batch_arg, single_lock_result, ContentResponse, CreateFolderResult, DeleteResult,
+        DownloadZipResult, ExportResult, FileMetadata, FolderMetadata, GetTagsResult,
+        ListFolderResult, LockFileBatchResult, LockFileResult, LockFileResultEntry, Metadata,
+        PathToTags, SaveUrlJobStatus, SaveUrlResult, SearchResult, UploadSessionCursor,
+        UploadSessionFinishBatchResult, UploadSessionFinishBatchResultEntry,
+        UploadSessionFinishEntry, UploadSessionStartResult, UploadSessionState,
+    },
+    middleware::{Middleware, MiddlewareChain, RequestParts, ResponseParts},
+    path::DropboxPath,
+    progress::{Progress, ProgressObserver},
+    rate_limit::{parse_retry_after, parse_retry_after_body, EndpointClass, RateLimiter},
+    sharing::{
+        AccessLevel, AddMember, FileMemberActionResult, FileMemberRemoveActionResult,
+        FolderPolicyOption, ListFoldersResult, MemberAccessLevelResult, MemberSelector,
+        RemoveMemberJobStatus, ShareFolderJobStatus, ShareFolderLaunch, SharedFileMembers,
+        SharedFileMetadata, SharedFolderMembers, SharedFolderMetadata, SharedLinkMetadata,
+        SharingJobLaunch, SharingJobStatus,
+    },
+    team::{MembersListResult, TeamInfo},
+    users::{BasicAccount, FullAccount, SpaceUsage},
+    DbxPathRootErrorSummary, DbxRequestErrorSummary, DbxRequestLimitsErrorSummary, DropboxError,
+    DropboxResult, ListFolderOption, MetadataOption, MetadataOptionBuilder, MoveCopyOption,
+    PathRoot, PathRootError, SearchOption, UploadOption,
 };
 #[cfg(feature = "non-blocking")]
 use async_trait::async_trait;
 #[cfg(feature = "non-blocking")]
 use reqwest::{header, header::HeaderMap, StatusCode};
+use serde::de::DeserializeOwned;
 use serde_json::json;
 #[cfg(feature = "blocking")]
 use std::io::Read;
-use std::time;
+#[cfg(feature = "blocking")]
+use std::thread;
+use std::{convert::TryInto, ops::Range, path::Path, sync::Arc, time};
 
 const CONTENT_END_POINT: &str = "https://content.dropboxapi.com";
 const OPERATION_END_POINT: &str = "https://api.dropboxapi.com";
+const API_ARG_HEADER: &str = "Dropbox-API-Arg";
+const API_RESULT_HEADER: &str = "Dropbox-API-Result";
+const PATH_ROOT_HEADER: &str = "Dropbox-API-Path-Root";
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+const REQUEST_ID_HEADER: &str = "X-Dropbox-Request-Id";
+///chunk size of uploads with progress reports and of resumable uploads
+pub(crate) const UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+///realignments of one chunk before `resume_upload` gives up
+const MAX_REALIGNS: u32 = 3;
+
+///path of the endpoint, e.g. `/2/files/upload`
+#[cfg(feature = "blocking")]
+fn endpoint_path(url: &str) -> &str {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    without_scheme
+        .find('/')
+        .map_or("/", |start| &without_scheme[start..])
+}
+
+///report an error response to the `tracing` and `metrics` features
+#[cfg(any(feature = "tracing", feature = "metrics"))]
+fn record_error(endpoint: &str, status: u16, request_id: Option<&str>, error: &DropboxError) {
+    #[cfg(feature = "tracing")]
+    trace::error_response(endpoint, status, request_id, error);
+    #[cfg(feature = "metrics")]
+    metric::record_error(endpoint, error);
+    #[cfg(not(feature = "tracing"))]
+    let _ = (status, request_id);
+}
+
+fn endpoint_class(url: &str) -> EndpointClass {
+    match url.starts_with(CONTENT_END_POINT) {
+        true => EndpointClass::Content,
+        false => EndpointClass::Rpc,
+    }
+}
+
+///the team member a team token acts as, see `DBXTeamClient::as_member`
+#[derive(Debug, Clone)]
+enum Select {
+    User(String),
+    Admin(String),
+}
+
+impl Select {
+    fn header(&self) -> (&'static str, &str) {
+        match self {
+            Select::User(id) => ("Dropbox-API-Select-User", id),
+            Select::Admin(id) => ("Dropbox-API-Select-Admin", id),
+        }
+    }
+}
+
+fn path_root_error(text: String) -> DropboxError {
+    match serde_json::from_str::<DbxPathRootErrorSummary>(&text) {
+        Ok(error_summary) => match error_summary.error {
+            PathRootError::InvalidRoot { invalid_root } => {
+                DropboxError::DbxInvalidRootError(invalid_root)
+            }
+            PathRootError::NoPermission => {
+                DropboxError::DbxAccessError(error_summary.error_summary)
+            }
+            PathRootError::Other => DropboxError::OtherError(error_summary.error_summary),
+        },
+        Err(_) => DropboxError::OtherError(text),
+    }
+}
+
+fn request_limits_error(text: String) -> DropboxError {
+    match serde_json::from_str::<DbxRequestLimitsErrorSummary>(&text) {
+        Ok(error_summary) => DropboxError::DbxRequestLimitsError(format!(
+            "{} , retry after {}",
+            error_summary.error_summary, error_summary.error.retry_after
+        )),
+        Err(_) => DropboxError::DbxRequestLimitsError(text),
+    }
+}
+
+///the `DropboxError` of the `error_summary` of a 409 response, e.g. `path/not_found/..`
+pub(crate) fn summary_error(error_summary: String) -> DropboxError {
+    let mut content = error_summary.split('/');
+    let tag = content.next().unwrap_or_default();
+    let reason = content.next().unwrap_or_default().to_string();
+    match tag {
+        "path" => DropboxError::DbxPathError(reason),
+        "from_lookup" => DropboxError::DbxFromLookUpError(reason),
+        "to" => DropboxError::DbxExistedError(reason),
+        _ => DropboxError::DbxConflictError(error_summary),
+    }
+}
+
+///the `DropboxError` of a tagged error in a result body, e.g. the failure of a batch entry
+///`{".tag": "path", "path": {".tag": "conflict", ...}}` is mapped like the summary `path/conflict`
+pub(crate) fn tagged_error(error: &serde_json::Value) -> DropboxError {
+    if let Some(correct_offset) = incorrect_offset(&json!({ "error": error }).to_string()) {
+        return DropboxError::DbxIncorrectOffsetError(correct_offset);
+    }
+    let mut tags = Vec::new();
+    let mut value = error;
+    while let Some(tag) = value[".tag"].as_str() {
+        tags.push(tag);
+        value = &value[tag];
+    }
+    match tags.is_empty() {
+        true => DropboxError::OtherError(error.to_string()),
+        false => summary_error(tags.join("/")),
+    }
+}
+
+///the offset an upload session expects, if the body is an `incorrect_offset` error
+///of the upload session endpoints
+pub(crate) fn incorrect_offset(text: &str) -> Option<u64> {
+    let body = serde_json::from_str::<serde_json::Value>(text).ok()?;
+    let error = match body["error"][".tag"].as_str()? {
+        "incorrect_offset" => &body["error"],
+        "lookup_failed" => &body["error"]["lookup_failed"],
+        _ => return None,
+    };
+    match error[".tag"].as_str()? {
+        "incorrect_offset" => error["correct_offset"].as_u64(),
+        _ => None,
+    }
+}
+
+///json body of a rpc endpoint
+struct Json<T>(T);
+
+///serialize the argument of a content endpoint for the `Dropbox-API-Arg` header
+///the header must be ASCII, so 0x7F and non-ASCII characters are escaped as `\uXXXX`
+pub(crate) fn api_arg_header(arg: &serde_json::Value) -> String {
+    let json = arg.to_string();
+    let mut header = String::with_capacity(json.len());
+    for c in json.chars() {
+        if (c as u32) < 0x7f {
+            header.push(c);
+            continue;
+        }
+        let mut units = [0u16; 2];
+        for unit in c.encode_utf16(&mut units) {
+            header.push_str(&format!("\\u{:04x}", unit));
+        }
+    }
+    header
+}
+
+///move the session to the offset the server expects, which must be a different offset inside the file
+///`retries` counts the realignments since the last acknowledged chunk
+pub(crate) fn realign(
+    state: &mut UploadSessionState,
+    offset: u64,
+    size: u64,
+    retries: u32,
+) -> DropboxResult<()> {
+    if retries > MAX_REALIGNS || offset == state.cursor.offset || offset > size {
+        return Err(DropboxError::DbxIncorrectOffsetError(offset));
+    }
+    state.cursor.offset = offset;
+    Ok(())
+}
+
+///remove a downloaded file which does not match the content hash of its metadata
+fn check_content_hash(
+    metadata: &FileMetadata,
+    content_hash: String,
+    local_path: &Path,
+) -> DropboxResult<()> {
+    match metadata.content_hash {
+        Some(ref expected) if *expected != content_hash => {
+            let _ = std::fs::remove_file(local_path);
+            Err(DropboxError::ContentHashMismatchError(format!(
+                "{}: expected {}, got {}",
+                local_path.display(),
+                expected,
+                content_hash
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn chunk_progress(transferred: u64, total: u64, chunk: u64) -> Progress {
+    Progress {
+        transferred,
+        total: Some(total),
+        chunk,
+    }
+}
+
+fn parse_api_result<T: DeserializeOwned>(header: Option<&str>) -> DropboxResult<T> {
+    match header {
+        Some(result) => Ok(serde_json::from_str(result)?),
+        None => Err(DropboxError::OtherError(format!(
+            "missing {} header",
+            API_RESULT_HEADER
+        ))),
+    }
+}
 
 #[cfg(feature = "non-blocking")]
 #[derive(Debug, Clone)]
 pub struct AsyncDBXClient {
     client: reqwest::Client,
+    path_root: Option<PathRoot>,
+    select: Option<Select>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    middleware: MiddlewareChain,
 }
 
 #[cfg(feature = "non-blocking")]
@@ -32,13 +274,181 @@ impl AsyncDBXClient {
             .default_headers(headers)
             .build()
             .unwrap();
-        Self { client }
+        Self {
+            client,
+            path_root: None,
+            select: None,
+            rate_limiter: None,
+            middleware: MiddlewareChain::default(),
+        }
+    }
+
+    ///send `Dropbox-API-Path-Root` with every request of this client
+    pub fn set_path_root(&mut self, path_root: PathRoot) {
+        self.path_root = Some(path_root);
+    }
+
+    ///return a client sharing the connection pool which sends `Dropbox-API-Path-Root`
+    ///e.g. `client.with_path_root(PathRoot::Home).upload(...)` for a single call
+    pub fn with_path_root(&self, path_root: PathRoot) -> Self {
+        Self {
+            path_root: Some(path_root),
+            ..self.clone()
+        }
+    }
+
+    ///wait for the limiter before every request of this client
+    ///clone the `Arc` into other clients to share the budget
+    pub fn set_rate_limiter(&mut self, rate_limiter: Arc<RateLimiter>) {
+        self.rate_limiter = Some(rate_limiter);
+    }
+
+    ///return a client sharing the connection pool which waits for the limiter
+    pub fn with_rate_limiter(&self, rate_limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            rate_limiter: Some(rate_limiter),
+            ..self.clone()
+        }
+    }
+
+    ///run the middleware around every request of this client, after the ones already added
+    pub fn add_middleware(&mut self, middleware: Arc<dyn Middleware>) {
+        self.middleware.push(middleware);
+    }
+
+    ///return a client sharing the connection pool which also runs the middleware
+    pub fn with_middleware(&self, middleware: Arc<dyn Middleware>) -> Self {
+        let mut client = self.clone();
+        client.add_middleware(middleware);
+        client
+    }
+
+    fn post(&self, url: &str) -> reqwest::RequestBuilder {
+        let mut builder = self.client.post(url);
+        if let Some(ref path_root) = self.path_root {
+            builder = builder.header(PATH_ROOT_HEADER, path_root.header_value());
+        }
+        if let Some(ref select) = self.select {
+            let (name, value) = select.header();
+            builder = builder.header(name, value);
+        }
+        builder
+    }
+
+    ///send the request, waiting for the rate limiter first
+    ///`retries` is how often the request was sent before, recorded by the `tracing` feature
+    async fn send(
+        &self,
+        builder: reqwest::RequestBuilder,
+        retries: u32,
+    ) -> DropboxResult<reqwest::Response> {
+        #[cfg(not(feature = "tracing"))]
+        let _ = retries;
+        let mut request = builder.build()?;
+        let class = endpoint_class(request.url().as_str());
+        #[cfg(feature = "tracing")]
+        let span = trace::request_span(
+            request.url().path(),
+            request
+                .headers()
+                .get(API_ARG_HEADER)
+                .and_then(|value| value.to_str().ok()),
+            request.body().and_then(|body| body.as_bytes()),
+            retries,
+        );
+        if let Some(ref limiter) = self.rate_limiter {
+            tokio::time::sleep(limiter.reserve(class)).await;
+        }
+        let endpoint = request.url().path().to_string();
+        if !self.middleware.is_empty() {
+            let headers = request
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+            let mut parts = RequestParts::new(&endpoint, headers);
+            self.middleware.before_send(&mut parts);
+            for (name, value) in parts.added_headers() {
+                let name = header::HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|e| DropboxError::OtherError(e.to_string()))?;
+                let value = header::HeaderValue::from_str(value)
+                    .map_err(|e| DropboxError::OtherError(e.to_string()))?;
+                request.headers_mut().insert(name, value);
+            }
+        }
+        #[cfg(feature = "metrics")]
+        metric::record_request(
+            &endpoint,
+            request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map_or(0, <[u8]>::len),
+        );
+        let start = time::Instant::now();
+        let res = self.client.execute(request);
+        #[cfg(feature = "tracing")]
+        let res = tracing::Instrument::instrument(res, span.clone());
+        let res = match res.await {
+            Ok(res) => res,
+            Err(e) => {
+                let error = DropboxError::from(e);
+                #[cfg(feature = "tracing")]
+                trace::record_failure(&span, &error, start.elapsed());
+                #[cfg(feature = "metrics")]
+                metric::record_failure(&endpoint, &error, start.elapsed());
+                return Err(error);
+            }
+        };
+        #[cfg(feature = "tracing")]
+        trace::record_response(
+            &span,
+            res.status().as_u16(),
+            res.headers()
+                .get(REQUEST_ID_HEADER)
+                .and_then(|value| value.to_str().ok()),
+            res.content_length(),
+            start.elapsed(),
+        );
+        #[cfg(feature = "metrics")]
+        metric::record_response(&endpoint, res.content_length(), start.elapsed());
+        if !self.middleware.is_empty() {
+            let headers = res
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+            let parts =
+                ResponseParts::new(&endpoint, res.status().as_u16(), headers, start.elapsed());
+            self.middleware.after_receive(&parts);
+        }
+        if let Some(ref limiter) = self.rate_limiter {
+            let retry_after = parse_retry_after(
+                res.headers()
+                    .get(header::RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok()),
+            );
+            //without the header the pause is only in the body, which is read here and
+            //turned into the error `handle_async_dbx_request_response` would return
+            if res.status() == StatusCode::TOO_MANY_REQUESTS && retry_after.is_none() {
+                #[cfg(any(feature = "tracing", feature = "metrics"))]
+                let request_id = res
+                    .headers()
+                    .get(REQUEST_ID_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                let text = res.text().await?;
+                limiter.on_response(class, 429, parse_retry_after_body(&text));
+                let error = request_limits_error(text);
+                #[cfg(any(feature = "tracing", feature = "metrics"))]
+                record_error(&endpoint, 429, request_id.as_deref(), &error);
+                return Err(error);
+            }
+            limiter.on_response(class, res.status().as_u16(), retry_after);
+        }
+        Ok(res)
     }
 
     pub async fn check_user(&self, ping_str: &str) -> DropboxResult<()> {
         let url = format!("{}{}", OPERATION_END_POINT, "/2/check/user");
-        let res = self
-            .client
+        let request = self
             .post(&url)
             .header("Content-Type", "application/json")
             .body(
@@ -48,77 +458,478 @@ impl AsyncDBXClient {
                 }
                 )
                 .to_string(),
-            )
-            .send()
+            );
+        let res = self.send(request, 0).await?;
+        handle_async_response_with(res, |res| async move {
+            match res.status() {
+                reqwest::StatusCode::BAD_REQUEST => {
+                    let text = res.text().await?;
+                    Err(DropboxError::DbxInvalidTokenError(text))
+                }
+                _ => async_dbx_response(res).await,
+            }
+        })
+        .await
+    }
+
+    ///binding /check/app
+    ///authenticate with the app key and secret instead of the access token
+    pub async fn check_app(
+        &self,
+        app_key: &str,
+        app_secret: &str,
+        ping_str: &str,
+    ) -> DropboxResult<()> {
+        let url = format!("{}{}", OPERATION_END_POINT, "/2/check/app");
+        let request = self
+            .client
+            .post(&url)
+            .basic_auth(app_key, Some(app_secret))
+            .header("Content-Type", "application/json")
+            .body(json!({ "query": ping_str }).to_string());
+        let res = self.send(request, 0).await?;
+        handle_async_response_with(res, |res| async move {
+            match res.status() {
+                StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED => {
+                    let text = res.text().await?;
+                    Err(DropboxError::DbxInvalidAppError(text))
+                }
+                _ => async_dbx_response(res).await,
+            }
+        })
+        .await
+    }
+
+    ///binding /auth/token/revoke
+    ///the client can not be used anymore after the token is revoked
+    pub async fn revoke_token(&self) -> DropboxResult<()> {
+        let res = self
+            .rpc_send("/2/auth/token/revoke", serde_json::Value::Null)
             .await?;
-        match res.status() {
-            reqwest::StatusCode::BAD_REQUEST => {
-                let text = res.text().await?;
-                return Err(DropboxError::DbxInvalidTokenError(text));
+        handle_async_dbx_request_response(res).await
+    }
+
+    ///binding /upload
+    pub async fn upload<P>(&self, file: Vec<u8>, path: P, option: UploadOption) -> DropboxResult<()>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        self.upload_to(file, &path, option).await.map(|_| ())
+    }
+
+    ///upload the file and add the tags to it, return the metadata of the uploaded file
+    ///the tags are added to the final path, so auto renamed uploads are tagged correctly
+    pub async fn upload_with_tags<P>(
+        &self,
+        file: Vec<u8>,
+        path: P,
+        option: UploadOption,
+        tags: &[&str],
+    ) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let metadata = self.upload_to(file, &path, option).await?;
+        let uploaded_path = metadata.path_display.as_deref().unwrap_or(path.as_str());
+        for tag in tags {
+            self.add_tag(uploaded_path, tag).await?;
+        }
+        Ok(metadata)
+    }
+
+    ///upload with progress reports to `observer`, return the metadata of the uploaded file
+    ///files bigger than 8 MB are sent in chunks through an upload session and reported after
+    ///every chunk, smaller files are reported once uploaded
+    pub async fn upload_with_progress<P>(
+        &self,
+        file: Vec<u8>,
+        path: P,
+        option: UploadOption,
+        observer: &dyn ProgressObserver,
+    ) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let total = file.len() as u64;
+        if file.len() <= UPLOAD_CHUNK_SIZE {
+            let metadata = self.upload_to(file, &path, option).await?;
+            observer.on_progress(chunk_progress(total, total, total));
+            return Ok(metadata);
+        }
+        let mut chunks = file.chunks(UPLOAD_CHUNK_SIZE);
+        let last = chunks.next_back().unwrap_or_default();
+        let mut cursor: Option<UploadSessionCursor> = None;
+        for chunk in chunks {
+            let next = match cursor {
+                Some(ref cursor) => {
+                    self.upload_session_append(cursor, chunk.to_vec(), false)
+                        .await?
+                }
+                None => self.upload_session_start(chunk.to_vec(), false).await?,
+            };
+            observer.on_progress(chunk_progress(next.offset, total, chunk.len() as u64));
+            cursor = Some(next);
+        }
+        let cursor = cursor.expect("files bigger than a chunk have several chunks");
+        let metadata = self
+            .upload_session_finish(&cursor, last.to_vec(), path.as_str(), option)
+            .await?;
+        observer.on_progress(chunk_progress(total, total, last.len() as u64));
+        Ok(metadata)
+    }
+
+    ///start an empty upload session for `resume_upload`, the path is checked before the session
+    ///is started
+    pub async fn begin_resumable_upload<P>(
+        &self,
+        path: P,
+        option: UploadOption,
+    ) -> DropboxResult<UploadSessionState>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let cursor = self.upload_session_start(Vec::new(), false).await?;
+        Ok(UploadSessionState {
+            cursor,
+            path: path.into(),
+            option,
+        })
+    }
+
+    ///upload the rest of `local_path` from the offset of `state` in 8 MB chunks and commit it
+    ///`on_chunk` is called with the new state after every acknowledged chunk so it can be
+    ///persisted, an `incorrect_offset` error realigns the state to the offset of the server
+    ///`observer` gets the progress over the whole file after every acknowledged chunk
+    pub async fn resume_upload(
+        &self,
+        state: &mut UploadSessionState,
+        local_path: &Path,
+        on_chunk: &mut (dyn FnMut(&UploadSessionState) + Send),
+        observer: &dyn ProgressObserver,
+    ) -> DropboxResult<FileMetadata> {
+        use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+        let mut file = tokio::fs::File::open(local_path).await.map_err(io_error)?;
+        let size = file.metadata().await.map_err(io_error)?.len();
+        let mut retries = 0;
+        loop {
+            file.seek(std::io::SeekFrom::Start(state.cursor.offset))
+                .await
+                .map_err(io_error)?;
+            let mut chunk = Vec::new();
+            (&mut file)
+                .take(UPLOAD_CHUNK_SIZE as u64)
+                .read_to_end(&mut chunk)
+                .await
+                .map_err(io_error)?;
+            let len = chunk.len() as u64;
+            //a chunk sent again after a realignment is a retry
+            let result = match state.cursor.offset + len >= size {
+                true => self
+                    .session_finish(&state.cursor, chunk, &state.path, &state.option, retries)
+                    .await
+                    .map(Some),
+                false => self
+                    .session_append(&state.cursor, chunk, false, retries)
+                    .await
+                    .map(|cursor| {
+                        state.cursor = cursor;
+                        None
+                    }),
+            };
+            match result {
+                Ok(Some(metadata)) => {
+                    observer.on_progress(chunk_progress(size, size, len));
+                    return Ok(metadata);
+                }
+                Ok(None) => {
+                    retries = 0;
+                    on_chunk(state);
+                    observer.on_progress(chunk_progress(state.cursor.offset, size, len));
+                }
+                Err(DropboxError::DbxIncorrectOffsetError(offset)) => {
+                    retries += 1;
+                    realign(state, offset, size, retries)?;
+                    on_chunk(state);
+                }
+                Err(e) => return Err(e),
             }
-            _ => handle_async_dbx_request_response(res).await,
         }
     }
-    ///binding /upload
-    pub async fn upload(
+
+    ///binding /upload, return the metadata of the uploaded file
+    pub async fn upload_file<P>(
         &self,
         file: Vec<u8>,
-        path: &str,
+        path: P,
         option: UploadOption,
-    ) -> DropboxResult<()> {
-        let mode = match option.mode {
-            UploadMode::Add => "add".to_string(),
-            UploadMode::Overwrite => "overwrite".to_string(),
-            UploadMode::Update(ref rev) => json!({
-                ".tag":"update",
-                "update":rev
-            })
-            .to_string(),
-        };
-        let url = format!("{}{}", CONTENT_END_POINT, "/2/files/upload");
+    ) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        self.upload_to(file, &path, option).await
+    }
+
+    async fn upload_to(
+        &self,
+        file: Vec<u8>,
+        path: &DropboxPath,
+        option: UploadOption,
+    ) -> DropboxResult<FileMetadata> {
         let res = self
-            .client
+            .content_upload("/2/files/upload", option.to_arg(path.as_str()), file, 0)
+            .await?;
+        handle_async_dbx_request_response::<Json<FileMetadata>>(res)
+            .await
+            .map(|json| json.0)
+    }
+
+    async fn content_upload(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+        body: Vec<u8>,
+        retries: u32,
+    ) -> DropboxResult<reqwest::Response> {
+        let url = format!("{}{}", CONTENT_END_POINT, end_point);
+        let request = self
             .post(&url)
             .header("Content-Type", "application/octet-stream")
-            .header(
-                "Dropbox-API-Arg",
-                json!({
-                    "path":path,
-                    "mode":mode,
-                    "autorename":option.allow_auto_rename,
-                    "mute":option.mute_notification,
-                    "strict_conflict":option.allow_strict_conflict
-                })
-                .to_string(),
+            .header(API_ARG_HEADER, api_arg_header(&arg))
+            .body(body);
+        let res = self.send(request, retries).await?;
+        Ok(res)
+    }
+
+    ///binding /download
+    pub async fn download<P>(&self, path: P) -> DropboxResult<Vec<u8>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let res = self
+            .content_download("/2/files/download", json!({ "path": path }))
+            .await?;
+        handle_async_dbx_request_response(res).await
+    }
+
+    ///binding /download, return the metadata of the file and its body to read chunk by chunk
+    pub async fn download_stream<P>(
+        &self,
+        path: P,
+    ) -> DropboxResult<AsyncContentStream<FileMetadata>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let res = self
+            .content_download("/2/files/download", json!({ "path": path }))
+            .await?;
+        handle_async_dbx_request_response(res).await
+    }
+
+    ///download with progress reports to `observer` after every received chunk
+    pub async fn download_with_progress<P>(
+        &self,
+        path: P,
+        observer: &dyn ProgressObserver,
+    ) -> DropboxResult<Vec<u8>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let res = self
+            .content_download("/2/files/download", json!({ "path": path }))
+            .await?;
+        let mut stream =
+            handle_async_dbx_request_response::<AsyncContentStream<FileMetadata>>(res).await?;
+        let total = stream.metadata.size;
+        let mut content = Vec::with_capacity(total as usize);
+        while let Some(chunk) = stream.chunk().await? {
+            content.extend_from_slice(&chunk);
+            observer.on_progress(chunk_progress(
+                content.len() as u64,
+                total,
+                chunk.len() as u64,
+            ));
+        }
+        Ok(content)
+    }
+
+    ///download the bytes `range` of the file, the end of the range is capped at the file size
+    pub async fn download_range<P>(&self, path: P, range: Range<u64>) -> DropboxResult<Vec<u8>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        if range.start >= range.end {
+            return Ok(Vec::new());
+        }
+        let res = self
+            .content_download_range(
+                "/2/files/download",
+                json!({ "path": path }),
+                Some(format!("bytes={}-{}", range.start, range.end - 1)),
             )
-            .body(file)
-            .send()
             .await?;
         handle_async_dbx_request_response(res).await
     }
 
-    ///binding /download
-    pub async fn download(&self, path: &str) -> DropboxResult<Vec<u8>> {
-        let url = format!("{}{}", CONTENT_END_POINT, "/2/files/download");
+    ///download the file into `local_path`, continuing from the size of a partial file left there
+    ///the rest is downloaded from the same revision and the finished file is checked against the
+    ///content hash, a file which does not match is removed and `ContentHashMismatchError` returned
+    pub async fn download_to_file<P>(
+        &self,
+        path: P,
+        local_path: &Path,
+    ) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let path = path.try_into()?;
+        let metadata = match self
+            .get_metadata(path.as_str(), MetadataOptionBuilder::new().build())
+            .await?
+        {
+            Metadata::File(metadata) => metadata,
+            _ => return Err(DropboxError::DbxPathError("not_file".to_string())),
+        };
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(local_path)
+            .await
+            .map_err(io_error)?;
+        let mut offset = file.metadata().await.map_err(io_error)?.len();
+        if offset > metadata.size {
+            file.set_len(0).await.map_err(io_error)?;
+            offset = 0;
+        }
+        if offset < metadata.size {
+            let res = self
+                .content_download_range(
+                    "/2/files/download",
+                    json!({ "path": format!("rev:{}", metadata.rev) }),
+                    Some(format!("bytes={}-", offset)),
+                )
+                .await?;
+            if res.status() == StatusCode::OK {
+                file.set_len(0).await.map_err(io_error)?;
+            }
+            let mut stream =
+                handle_async_dbx_request_response::<AsyncContentStream<FileMetadata>>(res).await?;
+            while let Some(chunk) = stream.chunk().await? {
+                file.write_all(&chunk).await.map_err(io_error)?;
+            }
+            file.flush().await.map_err(io_error)?;
+        }
+        drop(file);
+        let mut file = tokio::fs::File::open(local_path).await.map_err(io_error)?;
+        let mut hasher = ContentHasher::new();
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let read = file.read(&mut buf).await.map_err(io_error)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+        }
+        check_content_hash(&metadata, hasher.finish(), local_path)?;
+        Ok(metadata)
+    }
+
+    ///binding /get_preview
+    ///only for files with PDF or HTML preview, see dropbox documentation for the extensions
+    pub async fn get_preview(&self, path: &str) -> DropboxResult<ContentResponse<FileMetadata>> {
         let res = self
-            .client
-            .post(&url)
-            .header("Dropbox-API-Arg", json!({ "path": path }).to_string())
-            .send()
+            .content_download("/2/files/get_preview", json!({ "path": path }))
+            .await?;
+        handle_async_dbx_request_response(res).await
+    }
+
+    ///binding /export
+    ///for files that can not be downloaded directly, e.g. Paper docs or Google docs
+    pub async fn export(
+        &self,
+        path: &str,
+        export_format: Option<&str>,
+    ) -> DropboxResult<ContentResponse<ExportResult>> {
+        let mut arg = json!({ "path": path });
+        if let Some(format) = export_format {
+            arg["export_format"] = json!(format);
+        }
+        let res = self.content_download("/2/files/export", arg).await?;
+        handle_async_dbx_request_response(res).await
+    }
+
+    ///binding /download_zip
+    ///the folder must be less than 20 GB in size and have fewer than 10,000 total files
+    pub async fn download_zip(
+        &self,
+        path: &str,
+    ) -> DropboxResult<AsyncContentStream<DownloadZipResult>> {
+        let res = self
+            .content_download("/2/files/download_zip", json!({ "path": path }))
             .await?;
         handle_async_dbx_request_response(res).await
     }
 
+    async fn content_download(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+    ) -> DropboxResult<reqwest::Response> {
+        self.content_download_range(end_point, arg, None).await
+    }
+
+    ///`range` is the value of the `Range` header, e.g. `bytes=0-99`
+    async fn content_download_range(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+        range: Option<String>,
+    ) -> DropboxResult<reqwest::Response> {
+        let url = format!("{}{}", CONTENT_END_POINT, end_point);
+        let mut builder = self.post(&url).header(API_ARG_HEADER, api_arg_header(&arg));
+        if let Some(range) = range {
+            builder = builder.header(header::RANGE, range);
+        }
+        self.send(builder, 0).await
+    }
+
     // binding /move_v2
-    pub async fn move_file(
+    pub async fn move_file<P, Q>(
         &self,
-        from_path: &str,
-        to_path: &str,
+        from_path: P,
+        to_path: Q,
         option: MoveCopyOption,
-    ) -> DropboxResult<()> {
+    ) -> DropboxResult<()>
+    where
+        P: TryInto<DropboxPath>,
+        Q: TryInto<DropboxPath>,
+        DropboxError: From<P::Error> + From<Q::Error>,
+    {
+        let from_path = from_path.try_into()?;
+        let to_path = to_path.try_into()?;
         let url = format!("{}{}", OPERATION_END_POINT, "/2/files/move_v2");
-        let res = self
-            .client
+        let request = self
             .post(&url)
             .header("Content-Type", "application/json")
             .body(
@@ -132,21 +943,26 @@ impl AsyncDBXClient {
                 }
                 )
                 .to_string(),
-            )
-            .send()
-            .await?;
+            );
+        let res = self.send(request, 0).await?;
         handle_async_dbx_request_response(res).await
     }
 
-    pub async fn copy(
+    pub async fn copy<P, Q>(
         &self,
-        from_path: &str,
-        to_path: &str,
+        from_path: P,
+        to_path: Q,
         option: MoveCopyOption,
-    ) -> DropboxResult<()> {
+    ) -> DropboxResult<()>
+    where
+        P: TryInto<DropboxPath>,
+        Q: TryInto<DropboxPath>,
+        DropboxError: From<P::Error> + From<Q::Error>,
+    {
+        let from_path = from_path.try_into()?;
+        let to_path = to_path.try_into()?;
         let url = format!("{}{}", OPERATION_END_POINT, "/2/files/copy_v2");
-        let res = self
-            .client
+        let request = self
             .post(&url)
             .header("Content-Type", "application/json")
             .body(
@@ -160,251 +976,2613 @@ impl AsyncDBXClient {
                 }
                 )
                 .to_string(),
-            )
-            .send()
-            .await?;
+            );
+        let res = self.send(request, 0).await?;
         handle_async_dbx_request_response(res).await
     }
-}
 
-#[inline]
-#[cfg(feature = "non-blocking")]
-async fn handle_async_dbx_request_response<T: AsyncFrom<reqwest::Response>>(
-    res: reqwest::Response,
-) -> DropboxResult<T> {
-    if res.status() != StatusCode::OK {
-        match res.status() {
-            StatusCode::BAD_REQUEST => {
-                let text = res.text().await?;
-                return Err(DropboxError::DbxPathError(text));
-            }
-            StatusCode::UNAUTHORIZED => {
-                let error_summary = res.json::<DbxRequestErrorSummary>().await?;
-                return Err(DropboxError::DbxInvalidTokenError(
-                    error_summary.error_summary,
-                ));
-            }
-            StatusCode::FORBIDDEN => {
-                let error_summary = res.json::<DbxRequestErrorSummary>().await?;
-                return Err(DropboxError::DbxAccessError(error_summary.error_summary));
-            }
-            StatusCode::CONFLICT => {
-                let error_summary = res.json::<DbxRequestErrorSummary>().await?;
-                let content: Vec<&str> = error_summary.error_summary.split("/").collect();
-                match content[0] == "path" {
-                    true => return Err(DropboxError::DbxPathError(content[1].to_string())),
-                    false => match content[0] == "from_lookup" {
-                        true => {
-                            return Err(DropboxError::DbxFromLookUpError(content[1].to_string()))
-                        }
-                        false => match content[0] == "to" {
-                            true => {
-                                return Err(DropboxError::DbxExistedError(content[1].to_string()))
-                            }
-                            false => {
-                                return Err(DropboxError::DbxConflictError(
-                                    error_summary.error_summary,
-                                ))
-                            }
-                        },
-                    },
-                }
-            }
-            StatusCode::TOO_MANY_REQUESTS => {
-                let text = res.text().await?;
-                match serde_json::from_str::<DbxRequestLimitsErrorSummary>(&text) {
-                    Ok(error_summary) => {
-                        return Err(DropboxError::DbxRequestLimitsError(format!(
-                            "{} , retry after {}",
-                            error_summary.error_summary, error_summary.error.retry_after
-                        )));
-                    }
-                    Err(_) => {
-                        return Err(DropboxError::DbxRequestLimitsError(text));
-                    }
+    ///binding /save_url
+    ///the file is saved asynchronously, use `wait_save_url_job` to wait for the result
+    pub async fn save_url(&self, path: &str, url: &str) -> DropboxResult<SaveUrlResult> {
+        self.rpc_request("/2/files/save_url", json!({ "path": path, "url": url }))
+            .await
+    }
+
+    ///binding /save_url/check_job_status
+    pub async fn check_save_url_job_status(
+        &self,
+        async_job_id: &str,
+    ) -> DropboxResult<SaveUrlJobStatus> {
+        self.rpc_request(
+            "/2/files/save_url/check_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+        .await
+    }
+
+    ///poll /save_url/check_job_status every `interval` until the job is finished
+    pub async fn wait_save_url_job(
+        &self,
+        async_job_id: &str,
+        interval: time::Duration,
+    ) -> DropboxResult<FileMetadata> {
+        loop {
+            match self.check_save_url_job_status(async_job_id).await? {
+                SaveUrlJobStatus::InProgress => tokio::time::sleep(interval).await,
+                SaveUrlJobStatus::Complete(metadata) => return Ok(metadata),
+                SaveUrlJobStatus::Failed { failed } => {
+                    return Err(DropboxError::DbxSaveUrlError(failed))
                 }
             }
-            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
+        }
+    }
+
+    ///call /save_url and wait until the file is saved
+    pub async fn save_url_and_wait(
+        &self,
+        path: &str,
+        url: &str,
+        interval: time::Duration,
+    ) -> DropboxResult<FileMetadata> {
+        match self.save_url(path, url).await? {
+            SaveUrlResult::Complete(metadata) => Ok(metadata),
+            SaveUrlResult::AsyncJobId { async_job_id } => {
+                self.wait_save_url_job(&async_job_id, interval).await
+            }
+        }
+    }
+
+    ///binding /users/get_current_account
+    pub async fn get_current_account(&self) -> DropboxResult<FullAccount> {
+        self.rpc_request("/2/users/get_current_account", serde_json::Value::Null)
+            .await
+    }
+
+    ///binding /users/get_account
+    pub async fn get_account(&self, account_id: &str) -> DropboxResult<BasicAccount> {
+        self.rpc_request("/2/users/get_account", json!({ "account_id": account_id }))
+            .await
+    }
+
+    ///binding /users/get_account_batch
+    pub async fn get_account_batch(
+        &self,
+        account_ids: &[&str],
+    ) -> DropboxResult<Vec<BasicAccount>> {
+        self.rpc_request(
+            "/2/users/get_account_batch",
+            json!({ "account_ids": account_ids }),
+        )
+        .await
+    }
+
+    ///binding /users/get_space_usage
+    pub async fn get_space_usage(&self) -> DropboxResult<SpaceUsage> {
+        self.rpc_request("/2/users/get_space_usage", serde_json::Value::Null)
+            .await
+    }
+
+    ///binding /sharing/share_folder
+    ///sharing may run asynchronously, use `share_folder_and_wait` to wait for the result
+    pub async fn share_folder(
+        &self,
+        path: &str,
+        option: FolderPolicyOption,
+    ) -> DropboxResult<ShareFolderLaunch> {
+        let mut arg = serde_json::to_value(option)?;
+        arg["path"] = json!(path);
+        self.rpc_request("/2/sharing/share_folder", arg).await
+    }
+
+    ///binding /sharing/check_share_job_status
+    pub async fn check_share_job_status(
+        &self,
+        async_job_id: &str,
+    ) -> DropboxResult<ShareFolderJobStatus> {
+        self.rpc_request(
+            "/2/sharing/check_share_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+        .await
+    }
+
+    ///call /sharing/share_folder and poll the job every `interval` until it is finished
+    pub async fn share_folder_and_wait(
+        &self,
+        path: &str,
+        option: FolderPolicyOption,
+        interval: time::Duration,
+    ) -> DropboxResult<SharedFolderMetadata> {
+        let async_job_id = match self.share_folder(path, option).await? {
+            ShareFolderLaunch::Complete(metadata) => return Ok(metadata),
+            ShareFolderLaunch::AsyncJobId { async_job_id } => async_job_id,
+        };
+        loop {
+            match self.check_share_job_status(&async_job_id).await? {
+                ShareFolderJobStatus::InProgress => tokio::time::sleep(interval).await,
+                ShareFolderJobStatus::Complete(metadata) => return Ok(metadata),
+                ShareFolderJobStatus::Failed { failed } => {
+                    return Err(DropboxError::DbxShareFolderError(failed))
+                }
+            }
+        }
+    }
+
+    ///binding /sharing/add_folder_member
+    ///set `quiet` to not send an invitation email to the members
+    pub async fn add_folder_member(
+        &self,
+        shared_folder_id: &str,
+        members: &[AddMember],
+        quiet: bool,
+    ) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/sharing/add_folder_member",
+            json!({
+                "shared_folder_id": shared_folder_id,
+                "members": members,
+                "quiet": quiet
+            }),
+        )
+        .await
+    }
+
+    ///binding /sharing/remove_folder_member
+    ///the member is removed asynchronously, see `check_remove_member_job_status`
+    pub async fn remove_folder_member(
+        &self,
+        shared_folder_id: &str,
+        member: &MemberSelector,
+        leave_a_copy: bool,
+    ) -> DropboxResult<SharingJobLaunch> {
+        self.rpc_request(
+            "/2/sharing/remove_folder_member",
+            json!({
+                "shared_folder_id": shared_folder_id,
+                "member": member,
+                "leave_a_copy": leave_a_copy
+            }),
+        )
+        .await
+    }
+
+    ///binding /sharing/check_remove_member_job_status
+    pub async fn check_remove_member_job_status(
+        &self,
+        async_job_id: &str,
+    ) -> DropboxResult<RemoveMemberJobStatus> {
+        self.rpc_request(
+            "/2/sharing/check_remove_member_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+        .await
+    }
+
+    ///binding /sharing/list_folder_members
+    ///use `list_folder_members_continue` while the cursor is returned
+    pub async fn list_folder_members(
+        &self,
+        shared_folder_id: &str,
+        limit: Option<u32>,
+    ) -> DropboxResult<SharedFolderMembers> {
+        let mut arg = json!({ "shared_folder_id": shared_folder_id });
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/sharing/list_folder_members", arg)
+            .await
+    }
+
+    ///binding /sharing/list_folder_members/continue
+    pub async fn list_folder_members_continue(
+        &self,
+        cursor: &str,
+    ) -> DropboxResult<SharedFolderMembers> {
+        self.rpc_request(
+            "/2/sharing/list_folder_members/continue",
+            json!({ "cursor": cursor }),
+        )
+        .await
+    }
+
+    ///binding /sharing/update_folder_policy
+    pub async fn update_folder_policy(
+        &self,
+        shared_folder_id: &str,
+        option: FolderPolicyOption,
+    ) -> DropboxResult<SharedFolderMetadata> {
+        let mut arg = serde_json::to_value(option)?;
+        arg["shared_folder_id"] = json!(shared_folder_id);
+        self.rpc_request("/2/sharing/update_folder_policy", arg)
+            .await
+    }
+
+    ///binding /sharing/unshare_folder
+    ///unsharing may run asynchronously, see `check_job_status`
+    pub async fn unshare_folder(
+        &self,
+        shared_folder_id: &str,
+        leave_a_copy: bool,
+    ) -> DropboxResult<SharingJobLaunch> {
+        self.rpc_request(
+            "/2/sharing/unshare_folder",
+            json!({
+                "shared_folder_id": shared_folder_id,
+                "leave_a_copy": leave_a_copy
+            }),
+        )
+        .await
+    }
+
+    ///binding /sharing/check_job_status
+    pub async fn check_job_status(&self, async_job_id: &str) -> DropboxResult<SharingJobStatus> {
+        self.rpc_request(
+            "/2/sharing/check_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+        .await
+    }
+
+    ///binding /sharing/mount_folder
+    pub async fn mount_folder(
+        &self,
+        shared_folder_id: &str,
+    ) -> DropboxResult<SharedFolderMetadata> {
+        self.rpc_request(
+            "/2/sharing/mount_folder",
+            json!({ "shared_folder_id": shared_folder_id }),
+        )
+        .await
+    }
+
+    ///binding /sharing/unmount_folder
+    pub async fn unmount_folder(&self, shared_folder_id: &str) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/sharing/unmount_folder",
+            json!({ "shared_folder_id": shared_folder_id }),
+        )
+        .await
+    }
+
+    ///binding /sharing/list_folders
+    ///use `list_folders_continue` while the cursor is returned
+    pub async fn list_folders(&self, limit: Option<u32>) -> DropboxResult<ListFoldersResult> {
+        let mut arg = json!({});
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/sharing/list_folders", arg).await
+    }
+
+    ///binding /sharing/list_folders/continue
+    pub async fn list_folders_continue(&self, cursor: &str) -> DropboxResult<ListFoldersResult> {
+        self.rpc_request(
+            "/2/sharing/list_folders/continue",
+            json!({ "cursor": cursor }),
+        )
+        .await
+    }
+
+    ///binding /sharing/add_file_member
+    ///`file` is the path or id of the file, set `quiet` to not send an invitation email
+    pub async fn add_file_member(
+        &self,
+        file: &str,
+        members: &[MemberSelector],
+        access_level: AccessLevel,
+        quiet: bool,
+    ) -> DropboxResult<Vec<FileMemberActionResult>> {
+        self.rpc_request(
+            "/2/sharing/add_file_member",
+            json!({
+                "file": file,
+                "members": members,
+                "access_level": access_level,
+                "quiet": quiet
+            }),
+        )
+        .await
+    }
+
+    ///binding /sharing/list_file_members
+    ///use `list_file_members_continue` while the cursor is returned
+    pub async fn list_file_members(
+        &self,
+        file: &str,
+        limit: Option<u32>,
+    ) -> DropboxResult<SharedFileMembers> {
+        let mut arg = json!({ "file": file });
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/sharing/list_file_members", arg).await
+    }
+
+    ///binding /sharing/list_file_members/continue
+    pub async fn list_file_members_continue(
+        &self,
+        cursor: &str,
+    ) -> DropboxResult<SharedFileMembers> {
+        self.rpc_request(
+            "/2/sharing/list_file_members/continue",
+            json!({ "cursor": cursor }),
+        )
+        .await
+    }
+
+    ///binding /sharing/remove_file_member_2
+    pub async fn remove_file_member(
+        &self,
+        file: &str,
+        member: &MemberSelector,
+    ) -> DropboxResult<FileMemberRemoveActionResult> {
+        self.rpc_request(
+            "/2/sharing/remove_file_member_2",
+            json!({ "file": file, "member": member }),
+        )
+        .await
+    }
+
+    ///binding /sharing/update_file_member
+    pub async fn update_file_member(
+        &self,
+        file: &str,
+        member: &MemberSelector,
+        access_level: AccessLevel,
+    ) -> DropboxResult<MemberAccessLevelResult> {
+        self.rpc_request(
+            "/2/sharing/update_file_member",
+            json!({
+                "file": file,
+                "member": member,
+                "access_level": access_level
+            }),
+        )
+        .await
+    }
+
+    ///binding /sharing/unshare_file
+    pub async fn unshare_file(&self, file: &str) -> DropboxResult<()> {
+        self.rpc_request("/2/sharing/unshare_file", json!({ "file": file }))
+            .await
+    }
+
+    ///binding /sharing/get_file_metadata
+    pub async fn get_shared_file_metadata(&self, file: &str) -> DropboxResult<SharedFileMetadata> {
+        self.rpc_request("/2/sharing/get_file_metadata", json!({ "file": file }))
+            .await
+    }
+
+    ///binding /file_requests/create
+    ///`destination` is the folder uploads are saved to, it is created if it does not exist
+    pub async fn create_file_request(
+        &self,
+        title: &str,
+        destination: &str,
+        option: FileRequestOption,
+    ) -> DropboxResult<FileRequest> {
+        let mut arg = json!({
+            "title": title,
+            "destination": destination,
+            "open": option.open
+        });
+        if let Some(deadline) = option.deadline {
+            arg["deadline"] = json!(deadline);
+        }
+        if let Some(description) = option.description {
+            arg["description"] = json!(description);
+        }
+        self.rpc_request("/2/file_requests/create", arg).await
+    }
+
+    ///binding /file_requests/get
+    pub async fn get_file_request(&self, id: &str) -> DropboxResult<FileRequest> {
+        self.rpc_request("/2/file_requests/get", json!({ "id": id }))
+            .await
+    }
+
+    ///binding /file_requests/list_v2
+    ///use `list_file_requests_continue` with the cursor while `has_more` is true
+    pub async fn list_file_requests(
+        &self,
+        limit: Option<u32>,
+    ) -> DropboxResult<ListFileRequestsResult> {
+        let mut arg = json!({});
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/file_requests/list_v2", arg).await
+    }
+
+    ///binding /file_requests/list/continue
+    pub async fn list_file_requests_continue(
+        &self,
+        cursor: &str,
+    ) -> DropboxResult<ListFileRequestsResult> {
+        self.rpc_request(
+            "/2/file_requests/list/continue",
+            json!({ "cursor": cursor }),
+        )
+        .await
+    }
+
+    ///binding /file_requests/update
+    pub async fn update_file_request(
+        &self,
+        id: &str,
+        update: FileRequestUpdate,
+    ) -> DropboxResult<FileRequest> {
+        self.rpc_request("/2/file_requests/update", update.to_arg(id))
+            .await
+    }
+
+    ///binding /file_requests/delete
+    ///only closed file requests can be deleted
+    pub async fn delete_file_requests(
+        &self,
+        ids: &[&str],
+    ) -> DropboxResult<DeleteFileRequestsResult> {
+        self.rpc_request("/2/file_requests/delete", json!({ "ids": ids }))
+            .await
+    }
+
+    ///binding /file_requests/delete_all_closed
+    pub async fn delete_all_closed_file_requests(&self) -> DropboxResult<DeleteFileRequestsResult> {
+        self.rpc_request(
+            "/2/file_requests/delete_all_closed",
+            serde_json::Value::Null,
+        )
+        .await
+    }
+
+    ///binding /file_requests/count
+    pub async fn count_file_requests(&self) -> DropboxResult<u64> {
+        self.rpc_request::<CountFileRequestsResult>(
+            "/2/file_requests/count",
+            serde_json::Value::Null,
+        )
+        .await
+        .map(|count| count.file_request_count)
+    }
+
+    ///binding /lock_file_batch
+    pub async fn lock_file_batch(&self, paths: &[&str]) -> DropboxResult<Vec<LockFileResultEntry>> {
+        self.rpc_request::<LockFileBatchResult>("/2/files/lock_file_batch", lock_batch_arg(paths))
+            .await
+            .map(|result| result.entries)
+    }
+
+    ///lock a single file, the returned guard unlocks it on `release`
+    pub async fn lock_file(&self, path: &str) -> DropboxResult<AsyncFileLockGuard> {
+        let result = single_lock_result(self.lock_file_batch(&[path]).await?)?;
+        Ok(AsyncFileLockGuard {
+            client: self.clone(),
+            path: path.to_string(),
+            result,
+            released: false,
+        })
+    }
+
+    ///binding /unlock_file_batch
+    pub async fn unlock_file_batch(
+        &self,
+        paths: &[&str],
+    ) -> DropboxResult<Vec<LockFileResultEntry>> {
+        self.rpc_request::<LockFileBatchResult>("/2/files/unlock_file_batch", lock_batch_arg(paths))
+            .await
+            .map(|result| result.entries)
+    }
+
+    ///binding /get_file_lock_batch
+    pub async fn get_file_lock_batch(
+        &self,
+        paths: &[&str],
+    ) -> DropboxResult<Vec<LockFileResultEntry>> {
+        self.rpc_request::<LockFileBatchResult>(
+            "/2/files/get_file_lock_batch",
+            lock_batch_arg(paths),
+        )
+        .await
+        .map(|result| result.entries)
+    }
+
+    ///binding /tags/add
+    ///`tag_text` may only contain letters, digits and underscores
+    pub async fn add_tag(&self, path: &str, tag_text: &str) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/files/tags/add",
+            json!({ "path": path, "tag_text": tag_text }),
+        )
+        .await
+    }
+
+    ///binding /tags/remove
+    pub async fn remove_tag(&self, path: &str, tag_text: &str) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/files/tags/remove",
+            json!({ "path": path, "tag_text": tag_text }),
+        )
+        .await
+    }
+
+    ///binding /tags/get
+    pub async fn get_tags(&self, paths: &[&str]) -> DropboxResult<Vec<PathToTags>> {
+        self.rpc_request::<GetTagsResult>("/2/files/tags/get", json!({ "paths": paths }))
+            .await
+            .map(|result| result.paths_to_tags)
+    }
+
+    ///binding /list_folder
+    ///use `list_folder_continue` with the cursor while `has_more` is true
+    pub async fn list_folder(
+        &self,
+        path: &str,
+        option: ListFolderOption,
+    ) -> DropboxResult<ListFolderResult> {
+        self.rpc_request("/2/files/list_folder", option.to_arg(path))
+            .await
+    }
+
+    ///binding /list_folder/continue
+    pub async fn list_folder_continue(&self, cursor: &str) -> DropboxResult<ListFolderResult> {
+        self.rpc_request("/2/files/list_folder/continue", json!({ "cursor": cursor }))
+            .await
+    }
+
+    ///binding /get_metadata
+    pub async fn get_metadata(
+        &self,
+        path: &str,
+        option: MetadataOption,
+    ) -> DropboxResult<Metadata> {
+        self.rpc_request("/2/files/get_metadata", option.to_arg(path))
+            .await
+    }
+
+    ///binding /search_v2
+    ///use `search_continue` with the cursor while `has_more` is true
+    pub async fn search(&self, query: &str, option: SearchOption) -> DropboxResult<SearchResult> {
+        self.rpc_request("/2/files/search_v2", option.to_arg(query))
+            .await
+    }
+
+    ///binding /search/continue_v2
+    pub async fn search_continue(&self, cursor: &str) -> DropboxResult<SearchResult> {
+        self.rpc_request("/2/files/search/continue_v2", json!({ "cursor": cursor }))
+            .await
+    }
+
+    ///binding /file_properties/templates/add_for_user
+    ///return the id of the new template
+    pub async fn add_property_template(
+        &self,
+        name: &str,
+        description: &str,
+        fields: &[PropertyFieldTemplate],
+    ) -> DropboxResult<String> {
+        self.rpc_request::<TemplateIdResult>(
+            "/2/file_properties/templates/add_for_user",
+            json!({
+                "name": name,
+                "description": description,
+                "fields": fields
+            }),
+        )
+        .await
+        .map(|result| result.template_id)
+    }
+
+    ///binding /file_properties/templates/get_for_user
+    pub async fn get_property_template(
+        &self,
+        template_id: &str,
+    ) -> DropboxResult<PropertyGroupTemplate> {
+        self.rpc_request(
+            "/2/file_properties/templates/get_for_user",
+            json!({ "template_id": template_id }),
+        )
+        .await
+    }
+
+    ///binding /file_properties/templates/list_for_user
+    ///return the ids of all templates of the user
+    pub async fn list_property_templates(&self) -> DropboxResult<Vec<String>> {
+        self.rpc_request::<ListTemplateResult>(
+            "/2/file_properties/templates/list_for_user",
+            serde_json::Value::Null,
+        )
+        .await
+        .map(|result| result.template_ids)
+    }
+
+    ///binding /file_properties/templates/update_for_user
+    ///`None` keeps the current name or description, fields can only be added
+    pub async fn update_property_template(
+        &self,
+        template_id: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+        add_fields: &[PropertyFieldTemplate],
+    ) -> DropboxResult<()> {
+        let mut arg = json!({ "template_id": template_id });
+        if let Some(name) = name {
+            arg["name"] = json!(name);
+        }
+        if let Some(description) = description {
+            arg["description"] = json!(description);
+        }
+        if !add_fields.is_empty() {
+            arg["add_fields"] = json!(add_fields);
+        }
+        self.rpc_request::<TemplateIdResult>("/2/file_properties/templates/update_for_user", arg)
+            .await
+            .map(|_| ())
+    }
+
+    ///binding /file_properties/properties/add
+    pub async fn add_properties(
+        &self,
+        path: &str,
+        property_groups: &[PropertyGroup],
+    ) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/file_properties/properties/add",
+            json!({ "path": path, "property_groups": property_groups }),
+        )
+        .await
+    }
+
+    ///binding /file_properties/properties/overwrite
+    pub async fn overwrite_properties(
+        &self,
+        path: &str,
+        property_groups: &[PropertyGroup],
+    ) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/file_properties/properties/overwrite",
+            json!({ "path": path, "property_groups": property_groups }),
+        )
+        .await
+    }
+
+    ///binding /file_properties/properties/update
+    pub async fn update_properties(
+        &self,
+        path: &str,
+        updates: &[PropertyGroupUpdate],
+    ) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/file_properties/properties/update",
+            json!({ "path": path, "update_property_groups": updates }),
+        )
+        .await
+    }
+
+    ///binding /file_properties/properties/remove
+    pub async fn remove_properties(&self, path: &str, template_ids: &[&str]) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/file_properties/properties/remove",
+            json!({ "path": path, "property_template_ids": template_ids }),
+        )
+        .await
+    }
+
+    ///binding /file_properties/properties/search
+    pub async fn search_properties(
+        &self,
+        queries: &[PropertiesSearchQuery],
+    ) -> DropboxResult<PropertiesSearchResult> {
+        self.rpc_request(
+            "/2/file_properties/properties/search",
+            json!({
+                "queries": queries,
+                "template_filter": { ".tag": "filter_none" }
+            }),
+        )
+        .await
+    }
+
+    ///binding /delete_v2
+    ///with `parent_rev` the file is only deleted if it is still at that revision
+    pub async fn delete(&self, path: &str, parent_rev: Option<&str>) -> DropboxResult<Metadata> {
+        let mut arg = json!({ "path": path });
+        if let Some(rev) = parent_rev {
+            arg["parent_rev"] = json!(rev);
+        }
+        self.rpc_request::<DeleteResult>("/2/files/delete_v2", arg)
+            .await
+            .map(|result| result.metadata)
+    }
+
+    ///binding /create_folder_v2
+    pub async fn create_folder(
+        &self,
+        path: &str,
+        auto_rename: bool,
+    ) -> DropboxResult<FolderMetadata> {
+        self.rpc_request::<CreateFolderResult>(
+            "/2/files/create_folder_v2",
+            json!({ "path": path, "autorename": auto_rename }),
+        )
+        .await
+        .map(|result| result.metadata)
+    }
+
+    ///binding /create_shared_link_with_settings
+    ///fails with `DbxConflictError` if the path already has a shared link
+    pub async fn create_shared_link(&self, path: &str) -> DropboxResult<SharedLinkMetadata> {
+        self.rpc_request(
+            "/2/sharing/create_shared_link_with_settings",
+            json!({ "path": path }),
+        )
+        .await
+    }
+
+    ///binding /upload_session/start
+    ///set `close` when `data` is the last chunk, only closed sessions can be committed by
+    ///`upload_session_finish_batch`
+    pub async fn upload_session_start(
+        &self,
+        data: Vec<u8>,
+        close: bool,
+    ) -> DropboxResult<UploadSessionCursor> {
+        let offset = data.len() as u64;
+        let res = self
+            .content_upload(
+                "/2/files/upload_session/start",
+                json!({ "close": close }),
+                data,
+                0,
+            )
+            .await?;
+        let result = handle_async_dbx_request_response::<Json<UploadSessionStartResult>>(res)
+            .await?
+            .0;
+        Ok(UploadSessionCursor {
+            session_id: result.session_id,
+            offset,
+        })
+    }
+
+    ///binding /upload_session/append_v2
+    ///return the cursor after the appended data
+    pub async fn upload_session_append(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        close: bool,
+    ) -> DropboxResult<UploadSessionCursor> {
+        self.session_append(cursor, data, close, 0).await
+    }
+
+    ///`retries` is how often the chunk was sent before
+    async fn session_append(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        close: bool,
+        retries: u32,
+    ) -> DropboxResult<UploadSessionCursor> {
+        let offset = cursor.offset + data.len() as u64;
+        let res = self
+            .content_upload(
+                "/2/files/upload_session/append_v2",
+                json!({ "cursor": cursor, "close": close }),
+                data,
+                retries,
+            )
+            .await?;
+        handle_async_dbx_request_response::<()>(res).await?;
+        Ok(UploadSessionCursor {
+            session_id: cursor.session_id.clone(),
+            offset,
+        })
+    }
+
+    ///binding /upload_session/finish
+    ///`data` is the last chunk of the file, it may be empty
+    pub async fn upload_session_finish(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        path: &str,
+        option: UploadOption,
+    ) -> DropboxResult<FileMetadata> {
+        self.session_finish(cursor, data, path, &option, 0).await
+    }
+
+    ///`retries` is how often the chunk was sent before
+    async fn session_finish(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        path: &str,
+        option: &UploadOption,
+        retries: u32,
+    ) -> DropboxResult<FileMetadata> {
+        let res = self
+            .content_upload(
+                "/2/files/upload_session/finish",
+                json!({ "cursor": cursor, "commit": option.to_arg(path) }),
+                data,
+                retries,
+            )
+            .await?;
+        handle_async_dbx_request_response::<Json<FileMetadata>>(res)
+            .await
+            .map(|json| json.0)
+    }
+
+    ///binding /upload_session/finish_batch_v2
+    ///commit up to 1000 closed sessions at once, results are in the order of `entries`
+    pub async fn upload_session_finish_batch(
+        &self,
+        entries: &[UploadSessionFinishEntry],
+    ) -> DropboxResult<Vec<UploadSessionFinishBatchResultEntry>> {
+        let entries: Vec<serde_json::Value> = entries.iter().map(|entry| entry.to_arg()).collect();
+        self.rpc_request::<UploadSessionFinishBatchResult>(
+            "/2/files/upload_session/finish_batch_v2",
+            json!({ "entries": entries }),
+        )
+        .await
+        .map(|result| result.entries)
+    }
+
+    async fn rpc_request<T: DeserializeOwned + Send>(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+    ) -> DropboxResult<T> {
+        let res = self.rpc_send(end_point, arg).await?;
+        handle_async_dbx_request_response::<Json<T>>(res)
+            .await
+            .map(|json| json.0)
+    }
+
+    async fn rpc_send(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+    ) -> DropboxResult<reqwest::Response> {
+        let url = format!("{}{}", OPERATION_END_POINT, end_point);
+        let request = self
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .body(arg.to_string());
+        let res = self.send(request, 0).await?;
+        Ok(res)
+    }
+}
+
+#[inline]
+#[cfg(feature = "non-blocking")]
+async fn handle_async_dbx_request_response<T: AsyncFrom<reqwest::Response>>(
+    res: reqwest::Response,
+) -> DropboxResult<T> {
+    handle_async_response_with(res, async_dbx_response).await
+}
+
+///turn the response into `T` with `respond` and report its error
+#[cfg(feature = "non-blocking")]
+async fn handle_async_response_with<T, F, Fut>(
+    res: reqwest::Response,
+    respond: F,
+) -> DropboxResult<T>
+where
+    F: FnOnce(reqwest::Response) -> Fut,
+    Fut: std::future::Future<Output = DropboxResult<T>>,
+{
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    let (endpoint, status, request_id) = (
+        res.url().path().to_string(),
+        res.status().as_u16(),
+        res.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string),
+    );
+    let result = respond(res).await;
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    if let Err(ref error) = result {
+        record_error(&endpoint, status, request_id.as_deref(), error);
+    }
+    result
+}
+
+///turn the response into `T` or the `DropboxError` of its status
+#[cfg(feature = "non-blocking")]
+async fn async_dbx_response<T: AsyncFrom<reqwest::Response>>(
+    res: reqwest::Response,
+) -> DropboxResult<T> {
+    if res.status() != StatusCode::OK && res.status() != StatusCode::PARTIAL_CONTENT {
+        match res.status() {
+            StatusCode::BAD_REQUEST => {
+                let text = res.text().await?;
+                return Err(DropboxError::DbxPathError(text));
+            }
+            StatusCode::UNAUTHORIZED => {
+                let text = res.text().await?;
+                match serde_json::from_str::<DbxRequestErrorSummary>(&text) {
+                    Ok(error_summary) => {
+                        return Err(DropboxError::DbxInvalidTokenError(
+                            error_summary.error_summary,
+                        ));
+                    }
+                    Err(_) => {
+                        return Err(DropboxError::DbxInvalidTokenError(text));
+                    }
+                }
+            }
+            StatusCode::FORBIDDEN => {
+                let error_summary = res.json::<DbxRequestErrorSummary>().await?;
+                return Err(DropboxError::DbxAccessError(error_summary.error_summary));
+            }
+            StatusCode::CONFLICT => {
+                let text = res.text().await?;
+                if let Some(correct_offset) = incorrect_offset(&text) {
+                    return Err(DropboxError::DbxIncorrectOffsetError(correct_offset));
+                }
+                let error_summary = serde_json::from_str::<DbxRequestErrorSummary>(&text)?;
+                return Err(summary_error(error_summary.error_summary));
+            }
+            StatusCode::UNPROCESSABLE_ENTITY => {
+                let text = res.text().await?;
+                return Err(path_root_error(text));
+            }
+            StatusCode::TOO_MANY_REQUESTS => {
+                let text = res.text().await?;
+                return Err(request_limits_error(text));
+            }
+            StatusCode::INTERNAL_SERVER_ERROR | StatusCode::SERVICE_UNAVAILABLE => {
                 let text = res.text().await?;
                 return Err(DropboxError::DbxServerError(text));
             }
-            _ => {
-                let text = res.text().await?;
-                match serde_json::from_str::<DbxRequestErrorSummary>(&text) {
-                    Ok(error_summary) => {
-                        return Err(DropboxError::OtherError(error_summary.error_summary));
-                    }
-                    Err(_) => {
-                        return Err(DropboxError::OtherError(text));
-                    }
+            _ => {
+                let text = res.text().await?;
+                match serde_json::from_str::<DbxRequestErrorSummary>(&text) {
+                    Ok(error_summary) => {
+                        return Err(DropboxError::OtherError(error_summary.error_summary));
+                    }
+                    Err(_) => {
+                        return Err(DropboxError::OtherError(text));
+                    }
+                }
+            }
+        }
+    }
+    T::from(res).await.map(|i| *i)
+}
+
+#[cfg(feature = "non-blocking")]
+#[async_trait]
+trait AsyncFrom<T> {
+    async fn from(t: T) -> DropboxResult<Box<Self>>;
+}
+
+#[cfg(feature = "non-blocking")]
+#[async_trait]
+impl AsyncFrom<reqwest::Response> for Vec<u8> {
+    async fn from(res: reqwest::Response) -> DropboxResult<Box<Self>> {
+        res.bytes()
+            .await
+            .map(|b| Box::new(b.to_vec()))
+            .map_err(DropboxError::NonBlockingRequestError)
+    }
+}
+
+#[cfg(feature = "non-blocking")]
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncFrom<reqwest::Response> for ContentResponse<T> {
+    async fn from(res: reqwest::Response) -> DropboxResult<Box<Self>> {
+        let metadata = parse_api_result(
+            res.headers()
+                .get(API_RESULT_HEADER)
+                .and_then(|v| v.to_str().ok()),
+        )?;
+        let content = res.bytes().await?.to_vec();
+        Ok(Box::new(ContentResponse { metadata, content }))
+    }
+}
+
+///streaming body of a content endpoint, read it chunk by chunk with `chunk`
+#[cfg(feature = "non-blocking")]
+#[derive(Debug)]
+pub struct AsyncContentStream<T> {
+    pub metadata: T,
+    res: reqwest::Response,
+}
+
+#[cfg(feature = "non-blocking")]
+impl<T> AsyncContentStream<T> {
+    ///return `None` when the body is exhausted
+    pub async fn chunk(&mut self) -> DropboxResult<Option<Vec<u8>>> {
+        Ok(self.res.chunk().await?.map(|b| b.to_vec()))
+    }
+}
+
+#[cfg(feature = "non-blocking")]
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncFrom<reqwest::Response> for AsyncContentStream<T> {
+    async fn from(res: reqwest::Response) -> DropboxResult<Box<Self>> {
+        let metadata = parse_api_result(
+            res.headers()
+                .get(API_RESULT_HEADER)
+                .and_then(|v| v.to_str().ok()),
+        )?;
+        Ok(Box::new(AsyncContentStream { metadata, res }))
+    }
+}
+
+#[cfg(feature = "non-blocking")]
+#[async_trait]
+impl<T: DeserializeOwned + Send> AsyncFrom<reqwest::Response> for Json<T> {
+    async fn from(res: reqwest::Response) -> DropboxResult<Box<Self>> {
+        Ok(Box::new(Json(res.json::<T>().await?)))
+    }
+}
+
+#[cfg(feature = "non-blocking")]
+#[async_trait]
+impl AsyncFrom<reqwest::Response> for () {
+    async fn from(_res: reqwest::Response) -> DropboxResult<Box<Self>> {
+        DropboxResult::Ok(Box::new(()))
+    }
+}
+
+///lock of a single file, see `AsyncDBXClient::lock_file`
+///call `release` to unlock the file, when dropped inside a tokio runtime
+///the unlock is spawned as a task and its error is ignored
+#[cfg(feature = "non-blocking")]
+#[derive(Debug)]
+pub struct AsyncFileLockGuard {
+    client: AsyncDBXClient,
+    path: String,
+    result: LockFileResult,
+    released: bool,
+}
+
+#[cfg(feature = "non-blocking")]
+impl AsyncFileLockGuard {
+    pub fn lock(&self) -> &LockFileResult {
+        &self.result
+    }
+
+    ///binding /unlock_file_batch for the locked file
+    pub async fn release(mut self) -> DropboxResult<()> {
+        self.released = true;
+        let entries = self.client.unlock_file_batch(&[&self.path]).await?;
+        single_lock_result(entries).map(|_| ())
+    }
+}
+
+#[cfg(feature = "non-blocking")]
+impl Drop for AsyncFileLockGuard {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            let client = self.client.clone();
+            let path = std::mem::take(&mut self.path);
+            handle.spawn(async move {
+                let _ = client.unlock_file_batch(&[&path]).await;
+            });
+        }
+    }
+}
+
+#[cfg(feature = "non-blocking")]
+#[derive(Debug, Clone)]
+///client for a team scoped token
+pub struct AsyncDBXTeamClient {
+    inner: AsyncDBXClient,
+}
+
+#[cfg(feature = "non-blocking")]
+impl AsyncDBXTeamClient {
+    pub fn new(token: &str) -> Self {
+        Self {
+            inner: AsyncDBXClient::new(token),
+        }
+    }
+
+    ///return a user client acting as the team member, sending `Dropbox-API-Select-User`
+    pub fn as_member(&self, team_member_id: &str) -> AsyncDBXClient {
+        AsyncDBXClient {
+            select: Some(Select::User(team_member_id.to_string())),
+            ..self.inner.clone()
+        }
+    }
+
+    ///return a user client acting as the team admin, sending `Dropbox-API-Select-Admin`
+    ///the admin can access team folders in addition to the member's own files
+    pub fn as_admin(&self, team_member_id: &str) -> AsyncDBXClient {
+        AsyncDBXClient {
+            select: Some(Select::Admin(team_member_id.to_string())),
+            ..self.inner.clone()
+        }
+    }
+
+    ///binding /team/get_info
+    pub async fn get_info(&self) -> DropboxResult<TeamInfo> {
+        self.inner
+            .rpc_request("/2/team/get_info", serde_json::Value::Null)
+            .await
+    }
+
+    ///binding /team/members/list_v2
+    ///use `list_members_continue` with the cursor while `has_more` is true
+    pub async fn list_members(
+        &self,
+        limit: Option<u32>,
+        include_removed: bool,
+    ) -> DropboxResult<MembersListResult> {
+        let mut arg = json!({ "include_removed": include_removed });
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.inner.rpc_request("/2/team/members/list_v2", arg).await
+    }
+
+    ///binding /team/members/list/continue_v2
+    pub async fn list_members_continue(&self, cursor: &str) -> DropboxResult<MembersListResult> {
+        self.inner
+            .rpc_request(
+                "/2/team/members/list/continue_v2",
+                json!({ "cursor": cursor }),
+            )
+            .await
+    }
+}
+
+/////////////////////////////////////////////////////////////////////
+#[cfg(feature = "blocking")]
+//the blocking-io client
+#[derive(Clone)]
+pub struct DBXClient {
+    client: ureq::Agent,
+    token: String,
+    path_root: Option<PathRoot>,
+    select: Option<Select>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    middleware: MiddlewareChain,
+}
+
+#[cfg(feature = "blocking")]
+impl DBXClient {
+    pub fn new(token: &str) -> Self {
+        let client = ureq::AgentBuilder::new()
+            .timeout(time::Duration::from_secs(10))
+            .build();
+        let token = token.to_string();
+        Self {
+            client,
+            token,
+            path_root: None,
+            select: None,
+            rate_limiter: None,
+            middleware: MiddlewareChain::default(),
+        }
+    }
+
+    ///send `Dropbox-API-Path-Root` with every request of this client
+    pub fn set_path_root(&mut self, path_root: PathRoot) {
+        self.path_root = Some(path_root);
+    }
+
+    ///return a client sharing the connection pool which sends `Dropbox-API-Path-Root`
+    ///e.g. `client.with_path_root(PathRoot::Home).upload(...)` for a single call
+    pub fn with_path_root(&self, path_root: PathRoot) -> Self {
+        Self {
+            path_root: Some(path_root),
+            ..self.clone()
+        }
+    }
+
+    ///wait for the limiter before every request of this client
+    ///clone the `Arc` into other clients to share the budget
+    pub fn set_rate_limiter(&mut self, rate_limiter: Arc<RateLimiter>) {
+        self.rate_limiter = Some(rate_limiter);
+    }
+
+    ///return a client sharing the connection pool which waits for the limiter
+    pub fn with_rate_limiter(&self, rate_limiter: Arc<RateLimiter>) -> Self {
+        Self {
+            rate_limiter: Some(rate_limiter),
+            ..self.clone()
+        }
+    }
+
+    ///run the middleware around every request of this client, after the ones already added
+    pub fn add_middleware(&mut self, middleware: Arc<dyn Middleware>) {
+        self.middleware.push(middleware);
+    }
+
+    ///return a client sharing the connection pool which also runs the middleware
+    pub fn with_middleware(&self, middleware: Arc<dyn Middleware>) -> Self {
+        let mut client = self.clone();
+        client.add_middleware(middleware);
+        client
+    }
+
+    fn post(&self, url: &str) -> ureq::Request {
+        let mut request = self
+            .client
+            .post(url)
+            .set("Authorization", &format!("Bearer {}", self.token));
+        if let Some(ref path_root) = self.path_root {
+            request = request.set(PATH_ROOT_HEADER, &path_root.header_value());
+        }
+        if let Some(ref select) = self.select {
+            let (name, value) = select.header();
+            request = request.set(name, value);
+        }
+        request
+    }
+
+    ///send the request, waiting for the rate limiter first
+    ///`None` sends a request without body, `retries` is how often the request was sent before,
+    ///recorded by the `tracing` feature
+    fn send(
+        &self,
+        mut request: ureq::Request,
+        body: Option<&[u8]>,
+        retries: u32,
+    ) -> DropboxResult<ureq::Response> {
+        #[cfg(not(feature = "tracing"))]
+        let _ = retries;
+        let class = endpoint_class(request.url());
+        #[cfg(feature = "tracing")]
+        let span = trace::request_span(
+            endpoint_path(request.url()),
+            request.header(API_ARG_HEADER),
+            body,
+            retries,
+        );
+        #[cfg(feature = "tracing")]
+        let _entered = span.enter();
+        if let Some(ref limiter) = self.rate_limiter {
+            thread::sleep(limiter.reserve(class));
+        }
+        let endpoint = endpoint_path(request.url()).to_string();
+        if !self.middleware.is_empty() {
+            let names = request.header_names();
+            let headers = names
+                .iter()
+                .filter_map(|name| Some((name.as_str(), request.header(name)?)));
+            let mut parts = RequestParts::new(&endpoint, headers);
+            self.middleware.before_send(&mut parts);
+            for (name, value) in parts.added_headers() {
+                request = request.set(name, value);
+            }
+        }
+        #[cfg(feature = "metrics")]
+        metric::record_request(&endpoint, body.map_or(0, <[u8]>::len));
+        let start = time::Instant::now();
+        let res = match body {
+            Some(body) => request.send_bytes(body),
+            None => request.call(),
+        }
+        .or_else(status_response);
+        let res = match res {
+            Ok(res) => res,
+            Err(e) => {
+                let error = DropboxError::from(e);
+                #[cfg(feature = "tracing")]
+                trace::record_failure(&span, &error, start.elapsed());
+                #[cfg(feature = "metrics")]
+                metric::record_failure(&endpoint, &error, start.elapsed());
+                return Err(error);
+            }
+        };
+        #[cfg(feature = "tracing")]
+        trace::record_response(
+            &span,
+            res.status(),
+            res.header(REQUEST_ID_HEADER),
+            res.header("Content-Length")
+                .and_then(|len| len.parse().ok()),
+            start.elapsed(),
+        );
+        #[cfg(feature = "metrics")]
+        metric::record_response(
+            &endpoint,
+            res.header("Content-Length")
+                .and_then(|len| len.parse().ok()),
+            start.elapsed(),
+        );
+        if !self.middleware.is_empty() {
+            let names = res.headers_names();
+            let headers = names
+                .iter()
+                .filter_map(|name| Some((name.as_str(), res.header(name)?)));
+            let parts = ResponseParts::new(&endpoint, res.status(), headers, start.elapsed());
+            self.middleware.after_receive(&parts);
+        }
+        if let Some(ref limiter) = self.rate_limiter {
+            let retry_after = parse_retry_after(res.header("Retry-After"));
+            //without the header the pause is only in the body, which is read here and
+            //turned into the error `handle_dbx_request_response` would return
+            if res.status() == 429 && retry_after.is_none() {
+                #[cfg(any(feature = "tracing", feature = "metrics"))]
+                let request_id = res.header(REQUEST_ID_HEADER).map(str::to_string);
+                let text = res.into_string()?;
+                limiter.on_response(class, 429, parse_retry_after_body(&text));
+                let error = request_limits_error(text);
+                #[cfg(any(feature = "tracing", feature = "metrics"))]
+                record_error(&endpoint, 429, request_id.as_deref(), &error);
+                return Err(error);
+            }
+            limiter.on_response(class, res.status(), retry_after);
+        }
+        Ok(res)
+    }
+
+    pub fn check_user(&self, ping_str: &str) -> DropboxResult<()> {
+        let url = format!("{}{}", OPERATION_END_POINT, "/2/check/user");
+        let arg = json!(
+            {
+                "query":ping_str,
+            }
+        );
+        let request = self.post(&url).set("Content-Type", "application/json");
+        let res = self.send(request, Some(arg.to_string().as_bytes()), 0)?;
+        handle_response_with(res, |res| match res.status() {
+            400 => {
+                let text = res.into_string()?;
+                Err(DropboxError::DbxInvalidTokenError(text))
+            }
+            _ => dbx_response(res),
+        })
+    }
+
+    ///binding /check/app
+    ///authenticate with the app key and secret instead of the access token
+    pub fn check_app(&self, app_key: &str, app_secret: &str, ping_str: &str) -> DropboxResult<()> {
+        let url = format!("{}{}", OPERATION_END_POINT, "/2/check/app");
+        let request = self
+            .client
+            .post(&url)
+            .set("Authorization", &basic_auth_value(app_key, app_secret))
+            .set("Content-Type", "application/json");
+        let arg = json!({ "query": ping_str });
+        let res = self.send(request, Some(arg.to_string().as_bytes()), 0)?;
+        handle_response_with(res, |res| match res.status() {
+            400 | 401 => {
+                let text = res.into_string()?;
+                Err(DropboxError::DbxInvalidAppError(text))
+            }
+            _ => dbx_response(res),
+        })
+    }
+
+    ///binding /auth/token/revoke
+    ///the client can not be used anymore after the token is revoked
+    pub fn revoke_token(&self) -> DropboxResult<()> {
+        let res = self.rpc_send("/2/auth/token/revoke", serde_json::Value::Null)?;
+        handle_dbx_request_response(res)
+    }
+
+    ///binding /upload
+    pub fn upload<P>(&self, file: Vec<u8>, path: P, option: UploadOption) -> DropboxResult<()>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        self.upload_to(file, &path, option).map(|_| ())
+    }
+
+    ///upload the file and add the tags to it, return the metadata of the uploaded file
+    ///the tags are added to the final path, so auto renamed uploads are tagged correctly
+    pub fn upload_with_tags<P>(
+        &self,
+        file: Vec<u8>,
+        path: P,
+        option: UploadOption,
+        tags: &[&str],
+    ) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let metadata = self.upload_to(file, &path, option)?;
+        let uploaded_path = metadata.path_display.as_deref().unwrap_or(path.as_str());
+        for tag in tags {
+            self.add_tag(uploaded_path, tag)?;
+        }
+        Ok(metadata)
+    }
+
+    ///upload with progress reports to `observer`, return the metadata of the uploaded file
+    ///files bigger than 8 MB are sent in chunks through an upload session and reported after
+    ///every chunk, smaller files are reported once uploaded
+    pub fn upload_with_progress<P>(
+        &self,
+        file: Vec<u8>,
+        path: P,
+        option: UploadOption,
+        observer: &dyn ProgressObserver,
+    ) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let total = file.len() as u64;
+        if file.len() <= UPLOAD_CHUNK_SIZE {
+            let metadata = self.upload_to(file, &path, option)?;
+            observer.on_progress(chunk_progress(total, total, total));
+            return Ok(metadata);
+        }
+        let mut chunks = file.chunks(UPLOAD_CHUNK_SIZE);
+        let last = chunks.next_back().unwrap_or_default();
+        let mut cursor: Option<UploadSessionCursor> = None;
+        for chunk in chunks {
+            let next = match cursor {
+                Some(ref cursor) => self.upload_session_append(cursor, chunk.to_vec(), false)?,
+                None => self.upload_session_start(chunk.to_vec(), false)?,
+            };
+            observer.on_progress(chunk_progress(next.offset, total, chunk.len() as u64));
+            cursor = Some(next);
+        }
+        let cursor = cursor.expect("files bigger than a chunk have several chunks");
+        let metadata = self.upload_session_finish(&cursor, last.to_vec(), path.as_str(), option)?;
+        observer.on_progress(chunk_progress(total, total, last.len() as u64));
+        Ok(metadata)
+    }
+
+    ///start an empty upload session for `resume_upload`, the path is checked before the session
+    ///is started
+    pub fn begin_resumable_upload<P>(
+        &self,
+        path: P,
+        option: UploadOption,
+    ) -> DropboxResult<UploadSessionState>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let cursor = self.upload_session_start(Vec::new(), false)?;
+        Ok(UploadSessionState {
+            cursor,
+            path: path.into(),
+            option,
+        })
+    }
+
+    ///upload the rest of `local_path` from the offset of `state` in 8 MB chunks and commit it
+    ///`on_chunk` is called with the new state after every acknowledged chunk so it can be
+    ///persisted, an `incorrect_offset` error realigns the state to the offset of the server
+    ///`observer` gets the progress over the whole file after every acknowledged chunk
+    pub fn resume_upload(
+        &self,
+        state: &mut UploadSessionState,
+        local_path: &Path,
+        on_chunk: &mut dyn FnMut(&UploadSessionState),
+        observer: &dyn ProgressObserver,
+    ) -> DropboxResult<FileMetadata> {
+        use std::io::{Seek, SeekFrom};
+
+        let mut file = std::fs::File::open(local_path)?;
+        let size = file.metadata()?.len();
+        let mut retries = 0;
+        loop {
+            file.seek(SeekFrom::Start(state.cursor.offset))?;
+            let mut chunk = Vec::new();
+            (&mut file)
+                .take(UPLOAD_CHUNK_SIZE as u64)
+                .read_to_end(&mut chunk)?;
+            let len = chunk.len() as u64;
+            //a chunk sent again after a realignment is a retry
+            let result = match state.cursor.offset + len >= size {
+                true => self
+                    .session_finish(&state.cursor, chunk, &state.path, &state.option, retries)
+                    .map(Some),
+                false => self
+                    .session_append(&state.cursor, chunk, false, retries)
+                    .map(|cursor| {
+                        state.cursor = cursor;
+                        None
+                    }),
+            };
+            match result {
+                Ok(Some(metadata)) => {
+                    observer.on_progress(chunk_progress(size, size, len));
+                    return Ok(metadata);
+                }
+                Ok(None) => {
+                    retries = 0;
+                    on_chunk(state);
+                    observer.on_progress(chunk_progress(state.cursor.offset, size, len));
+                }
+                Err(DropboxError::DbxIncorrectOffsetError(offset)) => {
+                    retries += 1;
+                    realign(state, offset, size, retries)?;
+                    on_chunk(state);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    ///binding /upload, return the metadata of the uploaded file
+    pub fn upload_file<P>(
+        &self,
+        file: Vec<u8>,
+        path: P,
+        option: UploadOption,
+    ) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        self.upload_to(file, &path, option)
+    }
+
+    fn upload_to(
+        &self,
+        file: Vec<u8>,
+        path: &DropboxPath,
+        option: UploadOption,
+    ) -> DropboxResult<FileMetadata> {
+        let res = self.content_upload("/2/files/upload", option.to_arg(path.as_str()), &file, 0)?;
+        handle_dbx_request_response::<Json<FileMetadata>>(res).map(|json| json.0)
+    }
+
+    fn content_upload(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+        body: &[u8],
+        retries: u32,
+    ) -> DropboxResult<ureq::Response> {
+        let url = format!("{}{}", CONTENT_END_POINT, end_point);
+        let request = self
+            .post(&url)
+            .set("Content-Type", "application/octet-stream")
+            .set(API_ARG_HEADER, &api_arg_header(&arg));
+        let res = self.send(request, Some(body), retries)?;
+        Ok(res)
+    }
+
+    ///binding /download
+    pub fn download<P>(&self, path: P) -> DropboxResult<Vec<u8>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let res = self.content_download("/2/files/download", json!({ "path": path }))?;
+        handle_dbx_request_response(res)
+    }
+
+    ///binding /download, return the metadata of the file and a reader of its body
+    pub fn download_stream<P>(&self, path: P) -> DropboxResult<ContentStream<FileMetadata>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let res = self.content_download("/2/files/download", json!({ "path": path }))?;
+        handle_dbx_request_response(res)
+    }
+
+    ///download with progress reports to `observer` after every received chunk
+    pub fn download_with_progress<P>(
+        &self,
+        path: P,
+        observer: &dyn ProgressObserver,
+    ) -> DropboxResult<Vec<u8>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let res = self.content_download("/2/files/download", json!({ "path": path }))?;
+        let mut stream = handle_dbx_request_response::<ContentStream<FileMetadata>>(res)?;
+        let total = stream.metadata.size;
+        let mut content = Vec::with_capacity(total as usize);
+        let mut buf = vec![0; 64 * 1024];
+        loop {
+            let read = stream.reader.read(&mut buf)?;
+            if read == 0 {
+                return Ok(content);
+            }
+            content.extend_from_slice(&buf[..read]);
+            observer.on_progress(chunk_progress(content.len() as u64, total, read as u64));
+        }
+    }
+
+    ///download the bytes `range` of the file, the end of the range is capped at the file size
+    pub fn download_range<P>(&self, path: P, range: Range<u64>) -> DropboxResult<Vec<u8>>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        if range.start >= range.end {
+            return Ok(Vec::new());
+        }
+        let res = self.content_download_range(
+            "/2/files/download",
+            json!({ "path": path }),
+            Some(format!("bytes={}-{}", range.start, range.end - 1)),
+        )?;
+        handle_dbx_request_response(res)
+    }
+
+    ///download the file into `local_path`, continuing from the size of a partial file left there
+    ///the rest is downloaded from the same revision and the finished file is checked against the
+    ///content hash, a file which does not match is removed and `ContentHashMismatchError` returned
+    pub fn download_to_file<P>(&self, path: P, local_path: &Path) -> DropboxResult<FileMetadata>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        let path = path.try_into()?;
+        let metadata =
+            match self.get_metadata(path.as_str(), MetadataOptionBuilder::new().build())? {
+                Metadata::File(metadata) => metadata,
+                _ => return Err(DropboxError::DbxPathError("not_file".to_string())),
+            };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(local_path)?;
+        let mut offset = file.metadata()?.len();
+        if offset > metadata.size {
+            file.set_len(0)?;
+            offset = 0;
+        }
+        if offset < metadata.size {
+            let res = self.content_download_range(
+                "/2/files/download",
+                json!({ "path": format!("rev:{}", metadata.rev) }),
+                Some(format!("bytes={}-", offset)),
+            )?;
+            if res.status() == 200 {
+                file.set_len(0)?;
+            }
+            let mut stream = handle_dbx_request_response::<ContentStream<FileMetadata>>(res)?;
+            std::io::copy(&mut stream.reader, &mut file)?;
+        }
+        drop(file);
+        let mut hasher = ContentHasher::new();
+        std::io::copy(&mut std::fs::File::open(local_path)?, &mut hasher)?;
+        check_content_hash(&metadata, hasher.finish(), local_path)?;
+        Ok(metadata)
+    }
+
+    ///binding /get_preview
+    ///only for files with PDF or HTML preview, see dropbox documentation for the extensions
+    pub fn get_preview(&self, path: &str) -> DropboxResult<ContentResponse<FileMetadata>> {
+        let res = self.content_download("/2/files/get_preview", json!({ "path": path }))?;
+        handle_dbx_request_response(res)
+    }
+
+    ///binding /export
+    ///for files that can not be downloaded directly, e.g. Paper docs or Google docs
+    pub fn export(
+        &self,
+        path: &str,
+        export_format: Option<&str>,
+    ) -> DropboxResult<ContentResponse<ExportResult>> {
+        let mut arg = json!({ "path": path });
+        if let Some(format) = export_format {
+            arg["export_format"] = json!(format);
+        }
+        let res = self.content_download("/2/files/export", arg)?;
+        handle_dbx_request_response(res)
+    }
+
+    ///binding /download_zip
+    ///the folder must be less than 20 GB in size and have fewer than 10,000 total files
+    pub fn download_zip(&self, path: &str) -> DropboxResult<ContentStream<DownloadZipResult>> {
+        let res = self.content_download("/2/files/download_zip", json!({ "path": path }))?;
+        handle_dbx_request_response(res)
+    }
+
+    ///download the folder as zip and unpack it into `dest`
+    ///return the paths of the extracted files
+    #[cfg(feature = "extract")]
+    pub fn download_zip_to_dir(
+        &self,
+        path: &str,
+        dest: &std::path::Path,
+    ) -> DropboxResult<Vec<std::path::PathBuf>> {
+        let mut stream = self.download_zip(path)?;
+        let tmp_path = std::env::temp_dir().join(format!(
+            "rust-dropbox-{}-{}.zip",
+            std::process::id(),
+            time::SystemTime::now()
+                .duration_since(time::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or_default()
+        ));
+        let mut tmp_file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)?;
+        let extracted = std::io::copy(&mut stream.reader, &mut tmp_file)
+            .map_err(DropboxError::from)
+            .and_then(|_| crate::archive::extract_zip(&tmp_file, dest));
+        let _ = std::fs::remove_file(&tmp_path);
+        extracted
+    }
+
+    fn content_download(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+    ) -> DropboxResult<ureq::Response> {
+        self.content_download_range(end_point, arg, None)
+    }
+
+    ///`range` is the value of the `Range` header, e.g. `bytes=0-99`
+    fn content_download_range(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+        range: Option<String>,
+    ) -> DropboxResult<ureq::Response> {
+        let url = format!("{}{}", CONTENT_END_POINT, end_point);
+        let mut req = self.post(&url).set(API_ARG_HEADER, &api_arg_header(&arg));
+        if let Some(range) = range {
+            req = req.set("Range", &range);
+        }
+        let res = self.send(req, None, 0)?;
+        Ok(res)
+    }
+
+    // binding /move_v2
+    pub fn move_file<P, Q>(
+        &self,
+        from_path: P,
+        to_path: Q,
+        option: MoveCopyOption,
+    ) -> DropboxResult<()>
+    where
+        P: TryInto<DropboxPath>,
+        Q: TryInto<DropboxPath>,
+        DropboxError: From<P::Error> + From<Q::Error>,
+    {
+        let from_path = from_path.try_into()?;
+        let to_path = to_path.try_into()?;
+        let url = format!("{}{}", OPERATION_END_POINT, "/2/files/move_v2");
+        let arg = json!(
+            {
+                "from_path":from_path,
+                "to_path":to_path,
+                "allow_shared_folder": option.allow_shared_folder,
+                "autorename": option.auto_rename,
+                "allow_ownership_transfer": option.allow_ownership_transfer
+            }
+        );
+        let request = self.post(&url).set("Content-Type", "application/json");
+        let res = self.send(request, Some(arg.to_string().as_bytes()), 0)?;
+        handle_dbx_request_response(res)
+    }
+
+    pub fn copy<P, Q>(&self, from_path: P, to_path: Q, option: MoveCopyOption) -> DropboxResult<()>
+    where
+        P: TryInto<DropboxPath>,
+        Q: TryInto<DropboxPath>,
+        DropboxError: From<P::Error> + From<Q::Error>,
+    {
+        let from_path = from_path.try_into()?;
+        let to_path = to_path.try_into()?;
+        let url = format!("{}{}", OPERATION_END_POINT, "/2/files/copy_v2");
+        let arg = json!(
+            {
+                "from_path":from_path,
+                "to_path":to_path,
+                "allow_shared_folder": option.allow_shared_folder,
+                "autorename": option.auto_rename,
+                "allow_ownership_transfer": option.allow_ownership_transfer
+            }
+        );
+        let request = self.post(&url).set("Content-Type", "application/json");
+        let res = self.send(request, Some(arg.to_string().as_bytes()), 0)?;
+        handle_dbx_request_response(res)
+    }
+
+    ///binding /save_url
+    ///the file is saved asynchronously, use `wait_save_url_job` to wait for the result
+    pub fn save_url(&self, path: &str, url: &str) -> DropboxResult<SaveUrlResult> {
+        self.rpc_request("/2/files/save_url", json!({ "path": path, "url": url }))
+    }
+
+    ///binding /save_url/check_job_status
+    pub fn check_save_url_job_status(&self, async_job_id: &str) -> DropboxResult<SaveUrlJobStatus> {
+        self.rpc_request(
+            "/2/files/save_url/check_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+    }
+
+    ///poll /save_url/check_job_status every `interval` until the job is finished
+    pub fn wait_save_url_job(
+        &self,
+        async_job_id: &str,
+        interval: time::Duration,
+    ) -> DropboxResult<FileMetadata> {
+        loop {
+            match self.check_save_url_job_status(async_job_id)? {
+                SaveUrlJobStatus::InProgress => std::thread::sleep(interval),
+                SaveUrlJobStatus::Complete(metadata) => return Ok(metadata),
+                SaveUrlJobStatus::Failed { failed } => {
+                    return Err(DropboxError::DbxSaveUrlError(failed))
                 }
             }
         }
     }
-    T::from(res).await.map(|i| *i)
-}
 
-#[cfg(feature = "non-blocking")]
-#[async_trait]
-trait AsyncFrom<T> {
-    async fn from(t: T) -> DropboxResult<Box<Self>>;
-}
+    ///call /save_url and wait until the file is saved
+    pub fn save_url_and_wait(
+        &self,
+        path: &str,
+        url: &str,
+        interval: time::Duration,
+    ) -> DropboxResult<FileMetadata> {
+        match self.save_url(path, url)? {
+            SaveUrlResult::Complete(metadata) => Ok(metadata),
+            SaveUrlResult::AsyncJobId { async_job_id } => {
+                self.wait_save_url_job(&async_job_id, interval)
+            }
+        }
+    }
+
+    ///binding /users/get_current_account
+    pub fn get_current_account(&self) -> DropboxResult<FullAccount> {
+        self.rpc_request("/2/users/get_current_account", serde_json::Value::Null)
+    }
+
+    ///binding /users/get_account
+    pub fn get_account(&self, account_id: &str) -> DropboxResult<BasicAccount> {
+        self.rpc_request("/2/users/get_account", json!({ "account_id": account_id }))
+    }
+
+    ///binding /users/get_account_batch
+    pub fn get_account_batch(&self, account_ids: &[&str]) -> DropboxResult<Vec<BasicAccount>> {
+        self.rpc_request(
+            "/2/users/get_account_batch",
+            json!({ "account_ids": account_ids }),
+        )
+    }
+
+    ///binding /users/get_space_usage
+    pub fn get_space_usage(&self) -> DropboxResult<SpaceUsage> {
+        self.rpc_request("/2/users/get_space_usage", serde_json::Value::Null)
+    }
+
+    ///binding /sharing/share_folder
+    ///sharing may run asynchronously, use `share_folder_and_wait` to wait for the result
+    pub fn share_folder(
+        &self,
+        path: &str,
+        option: FolderPolicyOption,
+    ) -> DropboxResult<ShareFolderLaunch> {
+        let mut arg = serde_json::to_value(option)?;
+        arg["path"] = json!(path);
+        self.rpc_request("/2/sharing/share_folder", arg)
+    }
+
+    ///binding /sharing/check_share_job_status
+    pub fn check_share_job_status(
+        &self,
+        async_job_id: &str,
+    ) -> DropboxResult<ShareFolderJobStatus> {
+        self.rpc_request(
+            "/2/sharing/check_share_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+    }
+
+    ///call /sharing/share_folder and poll the job every `interval` until it is finished
+    pub fn share_folder_and_wait(
+        &self,
+        path: &str,
+        option: FolderPolicyOption,
+        interval: time::Duration,
+    ) -> DropboxResult<SharedFolderMetadata> {
+        let async_job_id = match self.share_folder(path, option)? {
+            ShareFolderLaunch::Complete(metadata) => return Ok(metadata),
+            ShareFolderLaunch::AsyncJobId { async_job_id } => async_job_id,
+        };
+        loop {
+            match self.check_share_job_status(&async_job_id)? {
+                ShareFolderJobStatus::InProgress => std::thread::sleep(interval),
+                ShareFolderJobStatus::Complete(metadata) => return Ok(metadata),
+                ShareFolderJobStatus::Failed { failed } => {
+                    return Err(DropboxError::DbxShareFolderError(failed))
+                }
+            }
+        }
+    }
+
+    ///binding /sharing/add_folder_member
+    ///set `quiet` to not send an invitation email to the members
+    pub fn add_folder_member(
+        &self,
+        shared_folder_id: &str,
+        members: &[AddMember],
+        quiet: bool,
+    ) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/sharing/add_folder_member",
+            json!({
+                "shared_folder_id": shared_folder_id,
+                "members": members,
+                "quiet": quiet
+            }),
+        )
+    }
+
+    ///binding /sharing/remove_folder_member
+    ///the member is removed asynchronously, see `check_remove_member_job_status`
+    pub fn remove_folder_member(
+        &self,
+        shared_folder_id: &str,
+        member: &MemberSelector,
+        leave_a_copy: bool,
+    ) -> DropboxResult<SharingJobLaunch> {
+        self.rpc_request(
+            "/2/sharing/remove_folder_member",
+            json!({
+                "shared_folder_id": shared_folder_id,
+                "member": member,
+                "leave_a_copy": leave_a_copy
+            }),
+        )
+    }
+
+    ///binding /sharing/check_remove_member_job_status
+    pub fn check_remove_member_job_status(
+        &self,
+        async_job_id: &str,
+    ) -> DropboxResult<RemoveMemberJobStatus> {
+        self.rpc_request(
+            "/2/sharing/check_remove_member_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+    }
+
+    ///binding /sharing/list_folder_members
+    ///use `list_folder_members_continue` while the cursor is returned
+    pub fn list_folder_members(
+        &self,
+        shared_folder_id: &str,
+        limit: Option<u32>,
+    ) -> DropboxResult<SharedFolderMembers> {
+        let mut arg = json!({ "shared_folder_id": shared_folder_id });
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/sharing/list_folder_members", arg)
+    }
+
+    ///binding /sharing/list_folder_members/continue
+    pub fn list_folder_members_continue(&self, cursor: &str) -> DropboxResult<SharedFolderMembers> {
+        self.rpc_request(
+            "/2/sharing/list_folder_members/continue",
+            json!({ "cursor": cursor }),
+        )
+    }
+
+    ///binding /sharing/update_folder_policy
+    pub fn update_folder_policy(
+        &self,
+        shared_folder_id: &str,
+        option: FolderPolicyOption,
+    ) -> DropboxResult<SharedFolderMetadata> {
+        let mut arg = serde_json::to_value(option)?;
+        arg["shared_folder_id"] = json!(shared_folder_id);
+        self.rpc_request("/2/sharing/update_folder_policy", arg)
+    }
+
+    ///binding /sharing/unshare_folder
+    ///unsharing may run asynchronously, see `check_job_status`
+    pub fn unshare_folder(
+        &self,
+        shared_folder_id: &str,
+        leave_a_copy: bool,
+    ) -> DropboxResult<SharingJobLaunch> {
+        self.rpc_request(
+            "/2/sharing/unshare_folder",
+            json!({
+                "shared_folder_id": shared_folder_id,
+                "leave_a_copy": leave_a_copy
+            }),
+        )
+    }
+
+    ///binding /sharing/check_job_status
+    pub fn check_job_status(&self, async_job_id: &str) -> DropboxResult<SharingJobStatus> {
+        self.rpc_request(
+            "/2/sharing/check_job_status",
+            json!({ "async_job_id": async_job_id }),
+        )
+    }
+
+    ///binding /sharing/mount_folder
+    pub fn mount_folder(&self, shared_folder_id: &str) -> DropboxResult<SharedFolderMetadata> {
+        self.rpc_request(
+            "/2/sharing/mount_folder",
+            json!({ "shared_folder_id": shared_folder_id }),
+        )
+    }
+
+    ///binding /sharing/unmount_folder
+    pub fn unmount_folder(&self, shared_folder_id: &str) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/sharing/unmount_folder",
+            json!({ "shared_folder_id": shared_folder_id }),
+        )
+    }
+
+    ///binding /sharing/list_folders
+    ///use `list_folders_continue` while the cursor is returned
+    pub fn list_folders(&self, limit: Option<u32>) -> DropboxResult<ListFoldersResult> {
+        let mut arg = json!({});
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/sharing/list_folders", arg)
+    }
+
+    ///binding /sharing/list_folders/continue
+    pub fn list_folders_continue(&self, cursor: &str) -> DropboxResult<ListFoldersResult> {
+        self.rpc_request(
+            "/2/sharing/list_folders/continue",
+            json!({ "cursor": cursor }),
+        )
+    }
+
+    ///binding /sharing/add_file_member
+    ///`file` is the path or id of the file, set `quiet` to not send an invitation email
+    pub fn add_file_member(
+        &self,
+        file: &str,
+        members: &[MemberSelector],
+        access_level: AccessLevel,
+        quiet: bool,
+    ) -> DropboxResult<Vec<FileMemberActionResult>> {
+        self.rpc_request(
+            "/2/sharing/add_file_member",
+            json!({
+                "file": file,
+                "members": members,
+                "access_level": access_level,
+                "quiet": quiet
+            }),
+        )
+    }
+
+    ///binding /sharing/list_file_members
+    ///use `list_file_members_continue` while the cursor is returned
+    pub fn list_file_members(
+        &self,
+        file: &str,
+        limit: Option<u32>,
+    ) -> DropboxResult<SharedFileMembers> {
+        let mut arg = json!({ "file": file });
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/sharing/list_file_members", arg)
+    }
+
+    ///binding /sharing/list_file_members/continue
+    pub fn list_file_members_continue(&self, cursor: &str) -> DropboxResult<SharedFileMembers> {
+        self.rpc_request(
+            "/2/sharing/list_file_members/continue",
+            json!({ "cursor": cursor }),
+        )
+    }
+
+    ///binding /sharing/remove_file_member_2
+    pub fn remove_file_member(
+        &self,
+        file: &str,
+        member: &MemberSelector,
+    ) -> DropboxResult<FileMemberRemoveActionResult> {
+        self.rpc_request(
+            "/2/sharing/remove_file_member_2",
+            json!({ "file": file, "member": member }),
+        )
+    }
+
+    ///binding /sharing/update_file_member
+    pub fn update_file_member(
+        &self,
+        file: &str,
+        member: &MemberSelector,
+        access_level: AccessLevel,
+    ) -> DropboxResult<MemberAccessLevelResult> {
+        self.rpc_request(
+            "/2/sharing/update_file_member",
+            json!({
+                "file": file,
+                "member": member,
+                "access_level": access_level
+            }),
+        )
+    }
+
+    ///binding /sharing/unshare_file
+    pub fn unshare_file(&self, file: &str) -> DropboxResult<()> {
+        self.rpc_request("/2/sharing/unshare_file", json!({ "file": file }))
+    }
+
+    ///binding /sharing/get_file_metadata
+    pub fn get_shared_file_metadata(&self, file: &str) -> DropboxResult<SharedFileMetadata> {
+        self.rpc_request("/2/sharing/get_file_metadata", json!({ "file": file }))
+    }
+
+    ///binding /file_requests/create
+    ///`destination` is the folder uploads are saved to, it is created if it does not exist
+    pub fn create_file_request(
+        &self,
+        title: &str,
+        destination: &str,
+        option: FileRequestOption,
+    ) -> DropboxResult<FileRequest> {
+        let mut arg = json!({
+            "title": title,
+            "destination": destination,
+            "open": option.open
+        });
+        if let Some(deadline) = option.deadline {
+            arg["deadline"] = json!(deadline);
+        }
+        if let Some(description) = option.description {
+            arg["description"] = json!(description);
+        }
+        self.rpc_request("/2/file_requests/create", arg)
+    }
+
+    ///binding /file_requests/get
+    pub fn get_file_request(&self, id: &str) -> DropboxResult<FileRequest> {
+        self.rpc_request("/2/file_requests/get", json!({ "id": id }))
+    }
+
+    ///binding /file_requests/list_v2
+    ///use `list_file_requests_continue` with the cursor while `has_more` is true
+    pub fn list_file_requests(&self, limit: Option<u32>) -> DropboxResult<ListFileRequestsResult> {
+        let mut arg = json!({});
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.rpc_request("/2/file_requests/list_v2", arg)
+    }
+
+    ///binding /file_requests/list/continue
+    pub fn list_file_requests_continue(
+        &self,
+        cursor: &str,
+    ) -> DropboxResult<ListFileRequestsResult> {
+        self.rpc_request(
+            "/2/file_requests/list/continue",
+            json!({ "cursor": cursor }),
+        )
+    }
+
+    ///binding /file_requests/update
+    pub fn update_file_request(
+        &self,
+        id: &str,
+        update: FileRequestUpdate,
+    ) -> DropboxResult<FileRequest> {
+        self.rpc_request("/2/file_requests/update", update.to_arg(id))
+    }
+
+    ///binding /file_requests/delete
+    ///only closed file requests can be deleted
+    pub fn delete_file_requests(&self, ids: &[&str]) -> DropboxResult<DeleteFileRequestsResult> {
+        self.rpc_request("/2/file_requests/delete", json!({ "ids": ids }))
+    }
 
-#[cfg(feature = "non-blocking")]
-#[async_trait]
-impl AsyncFrom<reqwest::Response> for Vec<u8> {
-    async fn from(res: reqwest::Response) -> DropboxResult<Box<Self>> {
-        res.bytes()
-            .await
-            .map(|b| Box::new(b.to_vec()))
-            .map_err(|e| DropboxError::NonBlockingRequestError(e))
+    ///binding /file_requests/delete_all_closed
+    pub fn delete_all_closed_file_requests(&self) -> DropboxResult<DeleteFileRequestsResult> {
+        self.rpc_request(
+            "/2/file_requests/delete_all_closed",
+            serde_json::Value::Null,
+        )
     }
-}
 
-#[cfg(feature = "non-blocking")]
-#[async_trait]
-impl AsyncFrom<reqwest::Response> for () {
-    async fn from(_res: reqwest::Response) -> DropboxResult<Box<Self>> {
-        DropboxResult::Ok(Box::new(()))
+    ///binding /file_requests/count
+    pub fn count_file_requests(&self) -> DropboxResult<u64> {
+        self.rpc_request::<CountFileRequestsResult>(
+            "/2/file_requests/count",
+            serde_json::Value::Null,
+        )
+        .map(|count| count.file_request_count)
     }
-}
 
-/////////////////////////////////////////////////////////////////////
-#[cfg(feature = "blocking")]
-//the blocking-io client
-pub struct DBXClient {
-    client: ureq::Agent,
-    token: String,
-}
+    ///binding /lock_file_batch
+    pub fn lock_file_batch(&self, paths: &[&str]) -> DropboxResult<Vec<LockFileResultEntry>> {
+        self.rpc_request::<LockFileBatchResult>("/2/files/lock_file_batch", lock_batch_arg(paths))
+            .map(|result| result.entries)
+    }
 
-#[cfg(feature = "blocking")]
-impl DBXClient {
-    pub fn new(token: &str) -> Self {
-        let client = ureq::AgentBuilder::new()
-            .timeout(time::Duration::from_secs(10))
-            .build();
-        let token = token.to_string();
-        Self { client, token }
+    ///lock a single file, the returned guard unlocks it when dropped
+    pub fn lock_file(&self, path: &str) -> DropboxResult<FileLockGuard> {
+        let result = single_lock_result(self.lock_file_batch(&[path])?)?;
+        Ok(FileLockGuard {
+            client: self.clone(),
+            path: path.to_string(),
+            result,
+            released: false,
+        })
     }
 
-    pub fn check_user(&self, ping_str: &str) -> DropboxResult<()> {
-        let url = format!("{}{}", OPERATION_END_POINT, "/2/check/user");
-        let res = self
-            .client
-            .post(&url)
-            .set("Authorization", &format!("Bearer {}", self.token))
-            .set("Content-Type", "application/json")
-            .send_json(json!(
-            {
-                "query":ping_str,
-            }
-            ))?;
-        match res.status() {
-            400 => {
-                let text = res.into_string()?;
-                return Err(DropboxError::DbxInvalidTokenError(text));
-            }
-            _ => handle_dbx_request_response(res),
-        }
+    ///binding /unlock_file_batch
+    pub fn unlock_file_batch(&self, paths: &[&str]) -> DropboxResult<Vec<LockFileResultEntry>> {
+        self.rpc_request::<LockFileBatchResult>("/2/files/unlock_file_batch", lock_batch_arg(paths))
+            .map(|result| result.entries)
     }
-    ///binding /upload
-    pub fn upload(&self, file: Vec<u8>, path: &str, option: UploadOption) -> DropboxResult<()> {
-        let mode = match option.mode {
-            UploadMode::Add => "add".to_string(),
-            UploadMode::Overwrite => "overwrite".to_string(),
-            UploadMode::Update(ref rev) => json!({
-                ".tag":"update",
-                "update":rev
-            })
-            .to_string(),
-        };
-        let url = format!("{}{}", CONTENT_END_POINT, "/2/files/upload");
-        let res = self
-            .client
-            .post(&url)
-            .set("Authorization", &format!("Bearer {}", self.token))
-            .set("Content-Type", "application/octet-stream")
-            .set(
-                "Dropbox-API-Arg",
-                json!({
-                    "path":path,
-                    "mode":mode,
-                    "autorename":option.allow_auto_rename,
-                    "mute":option.mute_notification,
-                    "strict_conflict":option.allow_strict_conflict
-                })
-                .to_string()
-                .as_str(),
-            )
-            .send_bytes(&file)?;
 
-        handle_dbx_request_response(res)
+    ///binding /get_file_lock_batch
+    pub fn get_file_lock_batch(&self, paths: &[&str]) -> DropboxResult<Vec<LockFileResultEntry>> {
+        self.rpc_request::<LockFileBatchResult>(
+            "/2/files/get_file_lock_batch",
+            lock_batch_arg(paths),
+        )
+        .map(|result| result.entries)
     }
 
-    ///binding /download
-    pub fn download(&self, path: &str) -> DropboxResult<Vec<u8>> {
-        let url = format!("{}{}", CONTENT_END_POINT, "/2/files/download");
-        let res = self
-            .client
-            .post(&url)
-            .set("Authorization", &format!("Bearer {}", self.token))
-            .set(
-                "Dropbox-API-Arg",
-                json!({ "path": path }).to_string().as_str(),
-            )
-            .call()?;
+    ///binding /tags/add
+    ///`tag_text` may only contain letters, digits and underscores
+    pub fn add_tag(&self, path: &str, tag_text: &str) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/files/tags/add",
+            json!({ "path": path, "tag_text": tag_text }),
+        )
+    }
 
-        handle_dbx_request_response(res)
+    ///binding /tags/remove
+    pub fn remove_tag(&self, path: &str, tag_text: &str) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/files/tags/remove",
+            json!({ "path": path, "tag_text": tag_text }),
+        )
     }
 
-    // binding /move_v2
-    pub fn move_file(
+    ///binding /tags/get
+    pub fn get_tags(&self, paths: &[&str]) -> DropboxResult<Vec<PathToTags>> {
+        self.rpc_request::<GetTagsResult>("/2/files/tags/get", json!({ "paths": paths }))
+            .map(|result| result.paths_to_tags)
+    }
+
+    ///binding /list_folder
+    ///use `list_folder_continue` with the cursor while `has_more` is true
+    pub fn list_folder(
         &self,
-        from_path: &str,
-        to_path: &str,
-        option: MoveCopyOption,
+        path: &str,
+        option: ListFolderOption,
+    ) -> DropboxResult<ListFolderResult> {
+        self.rpc_request("/2/files/list_folder", option.to_arg(path))
+    }
+
+    ///binding /list_folder/continue
+    pub fn list_folder_continue(&self, cursor: &str) -> DropboxResult<ListFolderResult> {
+        self.rpc_request("/2/files/list_folder/continue", json!({ "cursor": cursor }))
+    }
+
+    ///binding /get_metadata
+    pub fn get_metadata(&self, path: &str, option: MetadataOption) -> DropboxResult<Metadata> {
+        self.rpc_request("/2/files/get_metadata", option.to_arg(path))
+    }
+
+    ///binding /search_v2
+    ///use `search_continue` with the cursor while `has_more` is true
+    pub fn search(&self, query: &str, option: SearchOption) -> DropboxResult<SearchResult> {
+        self.rpc_request("/2/files/search_v2", option.to_arg(query))
+    }
+
+    ///binding /search/continue_v2
+    pub fn search_continue(&self, cursor: &str) -> DropboxResult<SearchResult> {
+        self.rpc_request("/2/files/search/continue_v2", json!({ "cursor": cursor }))
+    }
+
+    ///binding /file_properties/templates/add_for_user
+    ///return the id of the new template
+    pub fn add_property_template(
+        &self,
+        name: &str,
+        description: &str,
+        fields: &[PropertyFieldTemplate],
+    ) -> DropboxResult<String> {
+        self.rpc_request::<TemplateIdResult>(
+            "/2/file_properties/templates/add_for_user",
+            json!({
+                "name": name,
+                "description": description,
+                "fields": fields
+            }),
+        )
+        .map(|result| result.template_id)
+    }
+
+    ///binding /file_properties/templates/get_for_user
+    pub fn get_property_template(&self, template_id: &str) -> DropboxResult<PropertyGroupTemplate> {
+        self.rpc_request(
+            "/2/file_properties/templates/get_for_user",
+            json!({ "template_id": template_id }),
+        )
+    }
+
+    ///binding /file_properties/templates/list_for_user
+    ///return the ids of all templates of the user
+    pub fn list_property_templates(&self) -> DropboxResult<Vec<String>> {
+        self.rpc_request::<ListTemplateResult>(
+            "/2/file_properties/templates/list_for_user",
+            serde_json::Value::Null,
+        )
+        .map(|result| result.template_ids)
+    }
+
+    ///binding /file_properties/templates/update_for_user
+    ///`None` keeps the current name or description, fields can only be added
+    pub fn update_property_template(
+        &self,
+        template_id: &str,
+        name: Option<&str>,
+        description: Option<&str>,
+        add_fields: &[PropertyFieldTemplate],
     ) -> DropboxResult<()> {
-        let url = format!("{}{}", OPERATION_END_POINT, "/2/files/move_v2");
-        let res = self
-            .client
-            .post(&url)
-            .set("Content-Type", "application/json")
-            .set("Authorization", &format!("Bearer {}", self.token))
-            .send_json(json!(
-            {
-                "from_path":from_path,
-                "to_path":to_path,
-                "allow_shared_folder": option.allow_shared_folder,
-                "autorename": option.auto_rename,
-                "allow_ownership_transfer": option.allow_ownership_transfer
-            }
-            ))?;
-        handle_dbx_request_response(res)
+        let mut arg = json!({ "template_id": template_id });
+        if let Some(name) = name {
+            arg["name"] = json!(name);
+        }
+        if let Some(description) = description {
+            arg["description"] = json!(description);
+        }
+        if !add_fields.is_empty() {
+            arg["add_fields"] = json!(add_fields);
+        }
+        self.rpc_request::<TemplateIdResult>("/2/file_properties/templates/update_for_user", arg)
+            .map(|_| ())
     }
 
-    pub fn copy(
+    ///binding /file_properties/properties/add
+    pub fn add_properties(
         &self,
-        from_path: &str,
-        to_path: &str,
-        option: MoveCopyOption,
+        path: &str,
+        property_groups: &[PropertyGroup],
     ) -> DropboxResult<()> {
-        let url = format!("{}{}", OPERATION_END_POINT, "/2/files/copy_v2");
-        let res = self
-            .client
-            .post(&url)
-            .set("Content-Type", "application/json")
-            .set("Authorization", &format!("Bearer {}", self.token))
-            .send_json(json!(
-            {
-                "from_path":from_path,
-                "to_path":to_path,
-                "allow_shared_folder": option.allow_shared_folder,
-                "autorename": option.auto_rename,
-                "allow_ownership_transfer": option.allow_ownership_transfer
+        self.rpc_request(
+            "/2/file_properties/properties/add",
+            json!({ "path": path, "property_groups": property_groups }),
+        )
+    }
+
+    ///binding /file_properties/properties/overwrite
+    pub fn overwrite_properties(
+        &self,
+        path: &str,
+        property_groups: &[PropertyGroup],
+    ) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/file_properties/properties/overwrite",
+            json!({ "path": path, "property_groups": property_groups }),
+        )
+    }
+
+    ///binding /file_properties/properties/update
+    pub fn update_properties(
+        &self,
+        path: &str,
+        updates: &[PropertyGroupUpdate],
+    ) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/file_properties/properties/update",
+            json!({ "path": path, "update_property_groups": updates }),
+        )
+    }
+
+    ///binding /file_properties/properties/remove
+    pub fn remove_properties(&self, path: &str, template_ids: &[&str]) -> DropboxResult<()> {
+        self.rpc_request(
+            "/2/file_properties/properties/remove",
+            json!({ "path": path, "property_template_ids": template_ids }),
+        )
+    }
+
+    ///binding /file_properties/properties/search
+    pub fn search_properties(
+        &self,
+        queries: &[PropertiesSearchQuery],
+    ) -> DropboxResult<PropertiesSearchResult> {
+        self.rpc_request(
+            "/2/file_properties/properties/search",
+            json!({
+                "queries": queries,
+                "template_filter": { ".tag": "filter_none" }
+            }),
+        )
+    }
+
+    ///binding /delete_v2
+    ///with `parent_rev` the file is only deleted if it is still at that revision
+    pub fn delete(&self, path: &str, parent_rev: Option<&str>) -> DropboxResult<Metadata> {
+        let mut arg = json!({ "path": path });
+        if let Some(rev) = parent_rev {
+            arg["parent_rev"] = json!(rev);
+        }
+        self.rpc_request::<DeleteResult>("/2/files/delete_v2", arg)
+            .map(|result| result.metadata)
+    }
+
+    ///binding /create_folder_v2
+    pub fn create_folder(&self, path: &str, auto_rename: bool) -> DropboxResult<FolderMetadata> {
+        self.rpc_request::<CreateFolderResult>(
+            "/2/files/create_folder_v2",
+            json!({ "path": path, "autorename": auto_rename }),
+        )
+        .map(|result| result.metadata)
+    }
+
+    ///binding /create_shared_link_with_settings
+    ///fails with `DbxConflictError` if the path already has a shared link
+    pub fn create_shared_link(&self, path: &str) -> DropboxResult<SharedLinkMetadata> {
+        self.rpc_request(
+            "/2/sharing/create_shared_link_with_settings",
+            json!({ "path": path }),
+        )
+    }
+
+    ///binding /upload_session/start
+    ///set `close` when `data` is the last chunk, only closed sessions can be committed by
+    ///`upload_session_finish_batch`
+    pub fn upload_session_start(
+        &self,
+        data: Vec<u8>,
+        close: bool,
+    ) -> DropboxResult<UploadSessionCursor> {
+        let offset = data.len() as u64;
+        let res = self.content_upload(
+            "/2/files/upload_session/start",
+            json!({ "close": close }),
+            &data,
+            0,
+        )?;
+        let result = handle_dbx_request_response::<Json<UploadSessionStartResult>>(res)?.0;
+        Ok(UploadSessionCursor {
+            session_id: result.session_id,
+            offset,
+        })
+    }
+
+    ///binding /upload_session/append_v2
+    ///return the cursor after the appended data
+    pub fn upload_session_append(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        close: bool,
+    ) -> DropboxResult<UploadSessionCursor> {
+        self.session_append(cursor, data, close, 0)
+    }
+
+    ///`retries` is how often the chunk was sent before
+    fn session_append(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        close: bool,
+        retries: u32,
+    ) -> DropboxResult<UploadSessionCursor> {
+        let offset = cursor.offset + data.len() as u64;
+        let res = self.content_upload(
+            "/2/files/upload_session/append_v2",
+            json!({ "cursor": cursor, "close": close }),
+            &data,
+            retries,
+        )?;
+        handle_dbx_request_response::<()>(res)?;
+        Ok(UploadSessionCursor {
+            session_id: cursor.session_id.clone(),
+            offset,
+        })
+    }
+
+    ///binding /upload_session/finish
+    ///`data` is the last chunk of the file, it may be empty
+    pub fn upload_session_finish(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        path: &str,
+        option: UploadOption,
+    ) -> DropboxResult<FileMetadata> {
+        self.session_finish(cursor, data, path, &option, 0)
+    }
+
+    ///`retries` is how often the chunk was sent before
+    fn session_finish(
+        &self,
+        cursor: &UploadSessionCursor,
+        data: Vec<u8>,
+        path: &str,
+        option: &UploadOption,
+        retries: u32,
+    ) -> DropboxResult<FileMetadata> {
+        let res = self.content_upload(
+            "/2/files/upload_session/finish",
+            json!({ "cursor": cursor, "commit": option.to_arg(path) }),
+            &data,
+            retries,
+        )?;
+        handle_dbx_request_response::<Json<FileMetadata>>(res).map(|json| json.0)
+    }
+
+    ///binding /upload_session/finish_batch_v2
+    ///commit up to 1000 closed sessions at once, results are in the order of `entries`
+    pub fn upload_session_finish_batch(
+        &self,
+        entries: &[UploadSessionFinishEntry],
+    ) -> DropboxResult<Vec<UploadSessionFinishBatchResultEntry>> {
+        let entries: Vec<serde_json::Value> = entries.iter().map(|entry| entry.to_arg()).collect();
+        self.rpc_request::<UploadSessionFinishBatchResult>(
+            "/2/files/upload_session/finish_batch_v2",
+            json!({ "entries": entries }),
+        )
+        .map(|result| result.entries)
+    }
+
+    fn rpc_request<T: DeserializeOwned>(
+        &self,
+        end_point: &str,
+        arg: serde_json::Value,
+    ) -> DropboxResult<T> {
+        let res = self.rpc_send(end_point, arg)?;
+        handle_dbx_request_response::<Json<T>>(res).map(|json| json.0)
+    }
+
+    fn rpc_send(&self, end_point: &str, arg: serde_json::Value) -> DropboxResult<ureq::Response> {
+        let url = format!("{}{}", OPERATION_END_POINT, end_point);
+        let request = self.post(&url).set("Content-Type", "application/json");
+        let res = self.send(request, Some(arg.to_string().as_bytes()), 0)?;
+        Ok(res)
+    }
+}
+
+///ureq returns non 2xx responses as errors, hand them back to `handle_dbx_request_response`
+#[cfg(feature = "blocking")]
+fn status_response(e: ureq::Error) -> Result<ureq::Response, ureq::Error> {
+    match e {
+        ureq::Error::Status(_, res) => Ok(res),
+        e => Err(e),
+    }
+}
+
+#[cfg(feature = "blocking")]
+pub(crate) fn basic_auth_value(app_key: &str, app_secret: &str) -> String {
+    const TABLE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let input = format!("{}:{}", app_key, app_secret);
+    let mut encoded = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.as_bytes().chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(TABLE[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
             }
-            ))?;
-        handle_dbx_request_response(res)
+        }
     }
+    format!("Basic {}", encoded)
 }
 
 #[cfg(feature = "blocking")]
@@ -415,16 +3593,52 @@ trait FromRes<T> {
 #[cfg(feature = "blocking")]
 impl FromRes<ureq::Response> for Vec<u8> {
     fn from_res(res: ureq::Response) -> DropboxResult<Box<Self>> {
+        //chunked responses have no length, it is only a capacity hint
         let len = res
             .header("Content-Length")
             .and_then(|s| s.parse::<usize>().ok())
-            .unwrap();
+            .unwrap_or(0);
         let mut bytes: Vec<u8> = Vec::with_capacity(len);
         res.into_reader().read_to_end(&mut bytes)?;
         Ok(Box::new(bytes))
     }
 }
 
+#[cfg(feature = "blocking")]
+impl<T: DeserializeOwned> FromRes<ureq::Response> for ContentResponse<T> {
+    fn from_res(res: ureq::Response) -> DropboxResult<Box<Self>> {
+        let metadata = parse_api_result(res.header(API_RESULT_HEADER))?;
+        let mut content: Vec<u8> = Vec::new();
+        res.into_reader().read_to_end(&mut content)?;
+        Ok(Box::new(ContentResponse { metadata, content }))
+    }
+}
+
+///streaming body of a content endpoint
+#[cfg(feature = "blocking")]
+pub struct ContentStream<T> {
+    pub metadata: T,
+    pub reader: Box<dyn Read + Send + Sync + 'static>,
+}
+
+#[cfg(feature = "blocking")]
+impl<T: DeserializeOwned> FromRes<ureq::Response> for ContentStream<T> {
+    fn from_res(res: ureq::Response) -> DropboxResult<Box<Self>> {
+        let metadata = parse_api_result(res.header(API_RESULT_HEADER))?;
+        Ok(Box::new(ContentStream {
+            metadata,
+            reader: res.into_reader(),
+        }))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T: DeserializeOwned> FromRes<ureq::Response> for Json<T> {
+    fn from_res(res: ureq::Response) -> DropboxResult<Box<Self>> {
+        Ok(Box::new(Json(res.into_json::<T>()?)))
+    }
+}
+
 #[cfg(feature = "blocking")]
 impl FromRes<ureq::Response> for () {
     fn from_res(_res: ureq::Response) -> DropboxResult<Box<Self>> {
@@ -437,57 +3651,70 @@ impl FromRes<ureq::Response> for () {
 fn handle_dbx_request_response<T: FromRes<ureq::Response>>(
     res: ureq::Response,
 ) -> DropboxResult<T> {
-    if res.status() != 200 {
+    handle_response_with(res, dbx_response)
+}
+
+///turn the response into `T` with `respond` and report its error
+#[cfg(feature = "blocking")]
+fn handle_response_with<T>(
+    res: ureq::Response,
+    respond: impl FnOnce(ureq::Response) -> DropboxResult<T>,
+) -> DropboxResult<T> {
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    let (endpoint, status, request_id) = (
+        endpoint_path(res.get_url()).to_string(),
+        res.status(),
+        res.header(REQUEST_ID_HEADER).map(str::to_string),
+    );
+    let result = respond(res);
+    #[cfg(any(feature = "tracing", feature = "metrics"))]
+    if let Err(ref error) = result {
+        record_error(&endpoint, status, request_id.as_deref(), error);
+    }
+    result
+}
+
+///turn the response into `T` or the `DropboxError` of its status
+#[cfg(feature = "blocking")]
+fn dbx_response<T: FromRes<ureq::Response>>(res: ureq::Response) -> DropboxResult<T> {
+    if res.status() != 200 && res.status() != 206 {
         match res.status() {
             400 => {
                 let text = res.into_string()?;
                 return Err(DropboxError::DbxPathError(text));
             }
             401 => {
-                let error_summary = res.into_json::<DbxRequestErrorSummary>()?;
-                return Err(DropboxError::DbxInvalidTokenError(
-                    error_summary.error_summary,
-                ));
+                let text = res.into_string()?;
+                match serde_json::from_str::<DbxRequestErrorSummary>(&text) {
+                    Ok(error_summary) => {
+                        return Err(DropboxError::DbxInvalidTokenError(
+                            error_summary.error_summary,
+                        ));
+                    }
+                    Err(_) => {
+                        return Err(DropboxError::DbxInvalidTokenError(text));
+                    }
+                }
             }
             403 => {
                 let error_summary = res.into_json::<DbxRequestErrorSummary>()?;
                 return Err(DropboxError::DbxAccessError(error_summary.error_summary));
             }
             409 => {
-                let error_summary = res.into_json::<DbxRequestErrorSummary>()?;
-                let content: Vec<&str> = error_summary.error_summary.split("/").collect();
-                match content[0] == "path" {
-                    true => return Err(DropboxError::DbxPathError(content[1].to_string())),
-                    false => match content[0] == "from_lookup" {
-                        true => {
-                            return Err(DropboxError::DbxFromLookUpError(content[1].to_string()))
-                        }
-                        false => match content[0] == "to" {
-                            true => {
-                                return Err(DropboxError::DbxExistedError(content[1].to_string()))
-                            }
-                            false => {
-                                return Err(DropboxError::DbxConflictError(
-                                    error_summary.error_summary,
-                                ))
-                            }
-                        },
-                    },
+                let text = res.into_string()?;
+                if let Some(correct_offset) = incorrect_offset(&text) {
+                    return Err(DropboxError::DbxIncorrectOffsetError(correct_offset));
                 }
+                let error_summary = serde_json::from_str::<DbxRequestErrorSummary>(&text)?;
+                return Err(summary_error(error_summary.error_summary));
+            }
+            422 => {
+                let text = res.into_string()?;
+                return Err(path_root_error(text));
             }
             429 => {
                 let text = res.into_string()?;
-                match serde_json::from_str::<DbxRequestLimitsErrorSummary>(&text) {
-                    Ok(error_summary) => {
-                        return Err(DropboxError::DbxRequestLimitsError(format!(
-                            "{} , retry after {}",
-                            error_summary.error_summary, error_summary.error.retry_after
-                        )));
-                    }
-                    Err(_) => {
-                        return Err(DropboxError::DbxRequestLimitsError(text));
-                    }
-                }
+                return Err(request_limits_error(text));
             }
             500 | 503 => {
                 let text = res.into_string()?;
@@ -508,3 +3735,96 @@ fn handle_dbx_request_response<T: FromRes<ureq::Response>>(
     }
     T::from_res(res).map(|i| *i)
 }
+
+///lock of a single file, see `DBXClient::lock_file`
+///the file is unlocked on drop ignoring errors, call `release` to handle them
+#[cfg(feature = "blocking")]
+pub struct FileLockGuard {
+    client: DBXClient,
+    path: String,
+    result: LockFileResult,
+    released: bool,
+}
+
+#[cfg(feature = "blocking")]
+impl FileLockGuard {
+    pub fn lock(&self) -> &LockFileResult {
+        &self.result
+    }
+
+    ///binding /unlock_file_batch for the locked file
+    pub fn release(mut self) -> DropboxResult<()> {
+        self.released = true;
+        single_lock_result(self.client.unlock_file_batch(&[&self.path])?).map(|_| ())
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Drop for FileLockGuard {
+    fn drop(&mut self) {
+        if !self.released {
+            let _ = self.client.unlock_file_batch(&[&self.path]);
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+#[derive(Clone)]
+///client for a team scoped token
+pub struct DBXTeamClient {
+    inner: DBXClient,
+}
+
+#[cfg(feature = "blocking")]
+impl DBXTeamClient {
+    pub fn new(token: &str) -> Self {
+        Self {
+            inner: DBXClient::new(token),
+        }
+    }
+
+    ///return a user client acting as the team member, sending `Dropbox-API-Select-User`
+    pub fn as_member(&self, team_member_id: &str) -> DBXClient {
+        DBXClient {
+            select: Some(Select::User(team_member_id.to_string())),
+            ..self.inner.clone()
+        }
+    }
+
+    ///return a user client acting as the team admin, sending `Dropbox-API-Select-Admin`
+    ///the admin can access team folders in addition to the member's own files
+    pub fn as_admin(&self, team_member_id: &str) -> DBXClient {
+        DBXClient {
+            select: Some(Select::Admin(team_member_id.to_string())),
+            ..self.inner.clone()
+        }
+    }
+
+    ///binding /team/get_info
+    pub fn get_info(&self) -> DropboxResult<TeamInfo> {
+        self.inner
+            .rpc_request("/2/team/get_info", serde_json::Value::Null)
+    }
+
+    ///binding /team/members/list_v2
+    ///use `list_members_continue` with the cursor while `has_more` is true
+    pub fn list_members(
+        &self,
+        limit: Option<u32>,
+        include_removed: bool,
+    ) -> DropboxResult<MembersListResult> {
+        let mut arg = json!({ "include_removed": include_removed });
+        if let Some(limit) = limit {
+            arg["limit"] = json!(limit);
+        }
+        self.inner.rpc_request("/2/team/members/list_v2", arg)
+    }
+
+    ///binding /team/members/list/continue_v2
+    pub fn list_members_continue(&self, cursor: &str) -> DropboxResult<MembersListResult> {
+        self.inner.rpc_request(
+            "/2/team/members/list/continue_v2",
+            json!({ "cursor": cursor }),
+        )
+    }
+}
@@ -0,0 +1,63 @@
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+
+const BLOCK_SIZE: usize = 4 * 1024 * 1024;
+
+///dropbox content hash of the whole data, comparable to `FileMetadata::content_hash`
+pub fn content_hash(data: &[u8]) -> String {
+    let mut hasher = ContentHasher::new();
+    hasher.update(data);
+    hasher.finish()
+}
+
+///streaming dropbox content hash
+///the data is split into 4 MB blocks, the hash is the SHA-256 of the concatenated
+///SHA-256 hashes of the blocks, hex encoded
+#[derive(Clone, Default)]
+pub struct ContentHasher {
+    overall: Sha256,
+    block: Sha256,
+    block_len: usize,
+}
+
+impl ContentHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let take = (BLOCK_SIZE - self.block_len).min(data.len());
+            self.block.update(&data[..take]);
+            self.block_len += take;
+            data = &data[take..];
+            if self.block_len == BLOCK_SIZE {
+                let block = std::mem::take(&mut self.block);
+                self.overall.update(block.finalize());
+                self.block_len = 0;
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        if self.block_len > 0 {
+            self.overall.update(self.block.finalize());
+        }
+        self.overall
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+impl Write for ContentHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
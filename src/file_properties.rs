@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyField {
+    pub name: String,
+    pub value: String,
+}
+
+impl PropertyField {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+///values for the fields of a property template attached to a file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyGroup {
+    pub template_id: String,
+    pub fields: Vec<PropertyField>,
+}
+
+impl PropertyGroup {
+    pub fn new(template_id: &str, fields: Vec<PropertyField>) -> Self {
+        Self {
+            template_id: template_id.to_string(),
+            fields,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum PropertyType {
+    String,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropertyFieldTemplate {
+    pub name: String,
+    pub description: String,
+    #[serde(rename = "type")]
+    pub field_type: PropertyType,
+}
+
+impl PropertyFieldTemplate {
+    ///a string field, the only type dropbox supports for now
+    pub fn string(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            field_type: PropertyType::String,
+        }
+    }
+}
+
+///result of /templates/get_for_user
+#[derive(Debug, Clone, Deserialize)]
+pub struct PropertyGroupTemplate {
+    pub name: String,
+    pub description: String,
+    pub fields: Vec<PropertyFieldTemplate>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TemplateIdResult {
+    pub(crate) template_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ListTemplateResult {
+    pub(crate) template_ids: Vec<String>,
+}
+
+///changes to a property group for /properties/update
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertyGroupUpdate {
+    pub template_id: String,
+    pub add_or_update_fields: Vec<PropertyField>,
+    pub remove_fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum LogicalOperator {
+    OrOperator,
+}
+
+///query for /properties/search, matches files which have `query` as value of the field
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertiesSearchQuery {
+    query: String,
+    mode: serde_json::Value,
+    logical_operator: LogicalOperator,
+}
+
+impl PropertiesSearchQuery {
+    pub fn field_name(field_name: &str, query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+            mode: serde_json::json!({ ".tag": "field_name", "field_name": field_name }),
+            logical_operator: LogicalOperator::OrOperator,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PropertiesSearchMatch {
+    pub id: String,
+    pub path: String,
+    pub is_deleted: bool,
+    pub property_groups: Vec<PropertyGroup>,
+}
+
+///result of /properties/search
+#[derive(Debug, Clone, Deserialize)]
+pub struct PropertiesSearchResult {
+    pub matches: Vec<PropertiesSearchMatch>,
+    pub cursor: Option<String>,
+}
+
+///json of `include_property_groups` for the given template ids
+pub(crate) fn template_filter(template_ids: &[String]) -> serde_json::Value {
+    serde_json::json!({ ".tag": "filter_some", "filter_some": template_ids })
+}
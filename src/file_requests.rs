@@ -0,0 +1,192 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+///how long uploads are accepted after the deadline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum GracePeriod {
+    OneDay,
+    TwoDays,
+    SevenDays,
+    ThirtyDays,
+    Always,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRequestDeadline {
+    ///UTC timestamp formatted as `%Y-%m-%dT%H:%M:%SZ`
+    pub deadline: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_late_uploads: Option<GracePeriod>,
+}
+
+impl FileRequestDeadline {
+    pub fn new(deadline: &str, allow_late_uploads: Option<GracePeriod>) -> Self {
+        Self {
+            deadline: deadline.to_string(),
+            allow_late_uploads,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileRequest {
+    pub id: String,
+    pub url: String,
+    pub title: String,
+    pub created: String,
+    pub is_open: bool,
+    pub file_count: u64,
+    ///the path of the folder uploads are saved to, absent if the folder was removed
+    pub destination: Option<String>,
+    pub deadline: Option<FileRequestDeadline>,
+    pub description: Option<String>,
+}
+
+///result of /file_requests/list_v2 and /file_requests/list/continue
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListFileRequestsResult {
+    pub file_requests: Vec<FileRequest>,
+    pub cursor: String,
+    pub has_more: bool,
+}
+
+///result of /file_requests/delete and /file_requests/delete_all_closed
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeleteFileRequestsResult {
+    pub file_requests: Vec<FileRequest>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct CountFileRequestsResult {
+    pub(crate) file_request_count: u64,
+}
+
+#[derive(Clone)]
+pub struct FileRequestOption {
+    pub(crate) deadline: Option<FileRequestDeadline>,
+    pub(crate) open: bool,
+    pub(crate) description: Option<String>,
+}
+
+pub struct FileRequestOptionBuilder {
+    deadline: Option<FileRequestDeadline>,
+    open: bool,
+    description: Option<String>,
+}
+
+impl Default for FileRequestOptionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileRequestOptionBuilder {
+    ///new will return an option with follow value
+    ///deadline:none, open:"true", description:none
+    pub fn new() -> Self {
+        Self {
+            deadline: None,
+            open: true,
+            description: None,
+        }
+    }
+
+    pub fn set_deadline(&mut self, deadline: FileRequestDeadline) -> &mut FileRequestOptionBuilder {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    ///create the request closed, it does not accept uploads until it is opened
+    pub fn closed(&mut self) -> &mut FileRequestOptionBuilder {
+        self.open = false;
+        self
+    }
+
+    pub fn set_description(&mut self, description: &str) -> &mut FileRequestOptionBuilder {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn build(&self) -> FileRequestOption {
+        FileRequestOption {
+            deadline: self.deadline.clone(),
+            open: self.open,
+            description: self.description.clone(),
+        }
+    }
+}
+
+///changes for /file_requests/update, only the fields set on the builder are updated
+#[derive(Clone)]
+pub struct FileRequestUpdate {
+    arg: serde_json::Value,
+}
+
+impl FileRequestUpdate {
+    pub(crate) fn to_arg(&self, id: &str) -> serde_json::Value {
+        let mut arg = self.arg.clone();
+        arg["id"] = json!(id);
+        arg
+    }
+}
+
+pub struct FileRequestUpdateBuilder {
+    arg: serde_json::Value,
+}
+
+impl Default for FileRequestUpdateBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileRequestUpdateBuilder {
+    pub fn new() -> Self {
+        Self { arg: json!({}) }
+    }
+
+    pub fn set_title(&mut self, title: &str) -> &mut FileRequestUpdateBuilder {
+        self.arg["title"] = json!(title);
+        self
+    }
+
+    pub fn set_destination(&mut self, destination: &str) -> &mut FileRequestUpdateBuilder {
+        self.arg["destination"] = json!(destination);
+        self
+    }
+
+    ///`None` removes the deadline
+    pub fn set_deadline(
+        &mut self,
+        deadline: Option<FileRequestDeadline>,
+    ) -> &mut FileRequestUpdateBuilder {
+        let mut update = json!({ ".tag": "update" });
+        if let Some(deadline) = deadline {
+            update["deadline"] = json!(deadline.deadline);
+            if let Some(grace) = deadline.allow_late_uploads {
+                update["allow_late_uploads"] = json!(grace);
+            }
+        }
+        self.arg["deadline"] = update;
+        self
+    }
+
+    pub fn set_open(&mut self, open: bool) -> &mut FileRequestUpdateBuilder {
+        self.arg["open"] = json!(open);
+        self
+    }
+
+    pub fn set_description(&mut self, description: &str) -> &mut FileRequestUpdateBuilder {
+        self.arg["description"] = json!(description);
+        self
+    }
+
+    pub fn build(&self) -> FileRequestUpdate {
+        FileRequestUpdate {
+            arg: self.arg.clone(),
+        }
+    }
+}
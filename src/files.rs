@@ -0,0 +1,321 @@
+#![cfg_attr(feature = "blocking", allow(clippy::result_large_err))]
+use crate::{file_properties::PropertyGroup, UploadOption};
+use serde::{Deserialize, Serialize};
+
+///metadata of a file, as returned in `Dropbox-API-Result` or in json bodies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub name: String,
+    pub id: String,
+    pub client_modified: String,
+    pub server_modified: String,
+    pub rev: String,
+    pub size: u64,
+    pub path_lower: Option<String>,
+    pub path_display: Option<String>,
+    pub content_hash: Option<String>,
+    pub is_downloadable: Option<bool>,
+    pub has_explicit_shared_members: Option<bool>,
+    pub file_lock_info: Option<FileLockMetadata>,
+    pub property_groups: Option<Vec<PropertyGroup>>,
+}
+
+///metadata of a folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderMetadata {
+    pub name: String,
+    pub id: String,
+    pub path_lower: Option<String>,
+    pub path_display: Option<String>,
+    pub property_groups: Option<Vec<PropertyGroup>>,
+}
+
+///metadata of a deleted file or folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedMetadata {
+    pub name: String,
+    pub path_lower: Option<String>,
+    pub path_display: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum Metadata {
+    File(FileMetadata),
+    Folder(FolderMetadata),
+    Deleted(DeletedMetadata),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DownloadZipResult {
+    pub metadata: FolderMetadata,
+}
+
+///metadata of the exported content, see /export
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportMetadata {
+    pub name: String,
+    pub size: u64,
+    pub export_hash: Option<String>,
+    pub paper_revision: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportResult {
+    pub export_metadata: ExportMetadata,
+    pub file_metadata: FileMetadata,
+}
+
+///body of a content endpoint together with the parsed `Dropbox-API-Result` header
+#[derive(Debug, Clone)]
+pub struct ContentResponse<T> {
+    pub metadata: T,
+    pub content: Vec<u8>,
+}
+
+///result of /save_url
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum SaveUrlResult {
+    AsyncJobId { async_job_id: String },
+    Complete(FileMetadata),
+}
+
+///result of /save_url/check_job_status
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum SaveUrlJobStatus {
+    InProgress,
+    Complete(FileMetadata),
+    Failed { failed: SaveUrlError },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SaveUrlError {
+    ///the write error of the target path, e.g. `{".tag":"conflict",...}`
+    Path {
+        path: serde_json::Value,
+    },
+    DownloadFailed,
+    InvalidUrl,
+    NotFound,
+    #[serde(other)]
+    Other,
+}
+
+///lock information attached to `FileMetadata`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLockMetadata {
+    pub is_lockholder: Option<bool>,
+    pub lockholder_name: Option<String>,
+    pub lockholder_account_id: Option<String>,
+    pub created: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum FileLockContent {
+    Unlocked,
+    SingleUser {
+        lock_holder_account_id: String,
+        lock_holder_team_id: Option<String>,
+        created: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileLock {
+    pub content: FileLockContent,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LockFileResult {
+    pub metadata: Metadata,
+    pub lock: FileLock,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum LockFileError {
+    ///the path could not be looked up, e.g. `{".tag":"not_found"}`
+    PathLookup {
+        path_lookup: serde_json::Value,
+    },
+    TooManyWriteOperations,
+    TooManyFiles,
+    NoWritePermission,
+    CannotBeLocked,
+    FileNotShared,
+    ///the file is already locked by someone else
+    LockConflict {
+        lock: FileLock,
+    },
+    InternalError,
+    #[serde(other)]
+    Other,
+}
+
+///per-path result of /lock_file_batch, /unlock_file_batch and /get_file_lock_batch
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum LockFileResultEntry {
+    Success(LockFileResult),
+    Failure { failure: LockFileError },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct LockFileBatchResult {
+    pub(crate) entries: Vec<LockFileResultEntry>,
+}
+
+pub(crate) fn lock_batch_arg(paths: &[&str]) -> serde_json::Value {
+    let entries: Vec<serde_json::Value> = paths
+        .iter()
+        .map(|path| serde_json::json!({ "path": path }))
+        .collect();
+    serde_json::json!({ "entries": entries })
+}
+
+pub(crate) fn single_lock_result(
+    entries: Vec<LockFileResultEntry>,
+) -> crate::DropboxResult<LockFileResult> {
+    match entries.into_iter().next() {
+        Some(LockFileResultEntry::Success(result)) => Ok(result),
+        Some(LockFileResultEntry::Failure { failure }) => {
+            Err(crate::DropboxError::DbxLockFileError(failure))
+        }
+        None => Err(crate::DropboxError::OtherError(
+            "empty lock batch result".to_string(),
+        )),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum Tag {
+    UserGeneratedTag {
+        tag_text: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+///per-path result of /tags/get
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathToTags {
+    pub path: String,
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct GetTagsResult {
+    pub(crate) paths_to_tags: Vec<PathToTags>,
+}
+
+///result of /list_folder and /list_folder/continue
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListFolderResult {
+    pub entries: Vec<Metadata>,
+    pub cursor: String,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct CreateFolderResult {
+    pub(crate) metadata: FolderMetadata,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct DeleteResult {
+    pub(crate) metadata: Metadata,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum MetadataV2 {
+    Metadata {
+        metadata: Metadata,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchMatch {
+    pub metadata: MetadataV2,
+}
+
+///result of /search_v2 and /search/continue_v2
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchResult {
+    pub matches: Vec<SearchMatch>,
+    pub has_more: bool,
+    pub cursor: Option<String>,
+}
+
+///position in an upload session, `offset` is the number of bytes uploaded so far
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadSessionCursor {
+    pub session_id: String,
+    pub offset: u64,
+}
+
+///everything needed to continue an upload session, also after a restart of the process
+///persist it after every chunk, e.g. as json, and pass it to `resume_upload` again
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UploadSessionState {
+    pub cursor: UploadSessionCursor,
+    pub path: String,
+    pub option: UploadOption,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct UploadSessionStartResult {
+    pub(crate) session_id: String,
+}
+
+///a closed upload session to commit with `upload_session_finish_batch`
+#[derive(Clone)]
+pub struct UploadSessionFinishEntry {
+    pub cursor: UploadSessionCursor,
+    pub path: String,
+    pub option: UploadOption,
+}
+
+impl UploadSessionFinishEntry {
+    pub fn new(cursor: UploadSessionCursor, path: &str, option: UploadOption) -> Self {
+        Self {
+            cursor,
+            path: path.to_string(),
+            option,
+        }
+    }
+
+    pub(crate) fn to_arg(&self) -> serde_json::Value {
+        serde_json::json!({ "cursor": self.cursor, "commit": self.option.to_arg(&self.path) })
+    }
+}
+
+///per-entry result of /upload_session/finish_batch_v2, in the order of the entries
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum UploadSessionFinishBatchResultEntry {
+    Success(FileMetadata),
+    ///why the session could not be committed, e.g. `{".tag":"path","path":{".tag":"conflict"}}`
+    Failure {
+        failure: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct UploadSessionFinishBatchResult {
+    pub(crate) entries: Vec<UploadSessionFinishBatchResultEntry>,
+}
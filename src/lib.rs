@@ -1,7 +1,28 @@
+#[cfg(feature = "extract")]
+pub mod archive;
 pub mod client;
+pub mod content_hash;
+pub mod file_properties;
+pub mod file_requests;
+pub mod files;
+#[cfg(feature = "metrics")]
+pub mod metric;
+pub mod middleware;
+pub mod path;
+pub mod progress;
+pub mod rate_limit;
+pub mod sharing;
+#[cfg(feature = "blocking")]
+pub mod sync;
+pub mod team;
 mod test;
+#[cfg(feature = "tracing")]
+mod trace;
+pub mod transfer;
+pub mod users;
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 #[derive(Debug, Deserialize)]
 struct DbxRequestLimitsErrorSummary {
     error_summary: String,
@@ -10,20 +31,39 @@ struct DbxRequestLimitsErrorSummary {
 #[derive(Debug, Deserialize)]
 struct DbxRequestErrorSummary {
     error_summary: String,
+    #[allow(dead_code)]
     error: DbxRequestErrorTag,
 }
 
 #[derive(Debug, Deserialize)]
 struct DbxRequestErrorReason {
+    #[allow(dead_code)]
     reason: DbxRequestErrorTag,
     retry_after: u32,
 }
 #[derive(Debug, Deserialize)]
 struct DbxRequestErrorTag {
     #[serde(alias = ".tag")]
+    #[allow(dead_code)]
     tag: String,
 }
 #[derive(Debug, Deserialize)]
+struct DbxPathRootErrorSummary {
+    error_summary: String,
+    error: PathRootError,
+}
+#[derive(Debug, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+enum PathRootError {
+    InvalidRoot {
+        invalid_root: users::RootInfo,
+    },
+    NoPermission,
+    #[serde(other)]
+    Other,
+}
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct UserCheckResult {
     result: String,
 }
@@ -33,20 +73,56 @@ pub type DropboxResult<T> = std::result::Result<T, DropboxError>;
 pub enum DropboxError {
     #[cfg(feature = "non-blocking")]
     NonBlockingRequestError(reqwest::Error),
+    ///`ureq::Error` makes every `DropboxResult` large, the modules returning it allow
+    ///`clippy::result_large_err` with the `blocking` feature rather than boxing this variant
     #[cfg(feature = "blocking")]
     BlockingRequestError(ureq::Error),
     DbxUserCheckError(String),
     DbxPathError(String),
     DbxExistedError(String),
     DbxInvalidTokenError(String),
+    DbxInvalidAppError(String),
     DbxFromLookUpError(String),
     DbxRequestLimitsError(String),
     DbxAccessError(String),
+    ///the `Dropbox-API-Path-Root` header is not valid, the correct root is returned
+    DbxInvalidRootError(users::RootInfo),
     DbxConflictError(String),
+    ///the upload session expects the data at another offset, the correct offset is returned
+    DbxIncorrectOffsetError(u64),
     DbxServerError(String),
     OtherError(String),
+    ///the path was rejected before sending, see `path::DropboxPath`
+    InvalidPathError(String),
+    ///a downloaded file does not match the content hash dropbox reported for it
+    ContentHashMismatchError(String),
     #[cfg(feature = "blocking")]
     BodyParseError(std::io::Error),
+    JsonParseError(serde_json::Error),
+    DbxSaveUrlError(files::SaveUrlError),
+    DbxShareFolderError(sharing::ShareFolderError),
+    DbxLockFileError(files::LockFileError),
+    #[cfg(feature = "extract")]
+    ZipExtractError(zip::result::ZipError),
+}
+
+#[cfg(feature = "extract")]
+impl From<zip::result::ZipError> for DropboxError {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::ZipExtractError(e)
+    }
+}
+
+impl From<std::convert::Infallible> for DropboxError {
+    fn from(e: std::convert::Infallible) -> Self {
+        match e {}
+    }
+}
+
+impl From<serde_json::Error> for DropboxError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::JsonParseError(e)
+    }
 }
 
 #[cfg(feature = "blocking")]
@@ -56,6 +132,15 @@ impl From<std::io::Error> for DropboxError {
     }
 }
 
+///the io error as `BodyParseError` like the blocking client, as `OtherError` without it
+#[cfg(feature = "non-blocking")]
+pub(crate) fn io_error(e: std::io::Error) -> DropboxError {
+    #[cfg(feature = "blocking")]
+    return DropboxError::from(e);
+    #[cfg(not(feature = "blocking"))]
+    DropboxError::OtherError(e.to_string())
+}
+
 #[cfg(feature = "non-blocking")]
 impl From<reqwest::Error> for DropboxError {
     fn from(e: reqwest::Error) -> Self {
@@ -69,18 +154,26 @@ impl From<ureq::Error> for DropboxError {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct UploadOption {
     mode: UploadMode,
     allow_auto_rename: bool,
     mute_notification: bool,
     allow_strict_conflict: bool,
+    property_groups: Vec<file_properties::PropertyGroup>,
 }
 pub struct UploadOptionBuilder {
     mode: UploadMode,
     allow_auto_rename: bool,
     mute_notification: bool,
     allow_strict_conflict: bool,
+    property_groups: Vec<file_properties::PropertyGroup>,
+}
+
+impl Default for UploadOptionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl UploadOptionBuilder {
@@ -92,6 +185,7 @@ impl UploadOptionBuilder {
             allow_auto_rename: true,
             mute_notification: false,
             allow_strict_conflict: false,
+            property_groups: Vec::new(),
         }
     }
 
@@ -115,16 +209,50 @@ impl UploadOptionBuilder {
         self
     }
 
+    ///attach the property group to the uploaded file
+    pub fn add_property_group(
+        &mut self,
+        group: file_properties::PropertyGroup,
+    ) -> &mut UploadOptionBuilder {
+        self.property_groups.push(group);
+        self
+    }
+
     pub fn build(&self) -> UploadOption {
         UploadOption {
             mode: self.mode.clone(),
             allow_auto_rename: self.allow_auto_rename,
             mute_notification: self.mute_notification,
             allow_strict_conflict: self.allow_strict_conflict,
+            property_groups: self.property_groups.clone(),
         }
     }
 }
 
+impl UploadOption {
+    fn to_arg(&self, path: &str) -> serde_json::Value {
+        let mode = match self.mode {
+            UploadMode::Add => json!("add"),
+            UploadMode::Overwrite => json!("overwrite"),
+            UploadMode::Update(ref rev) => json!({
+                ".tag":"update",
+                "update":rev
+            }),
+        };
+        let mut arg = json!({
+            "path":path,
+            "mode":mode,
+            "autorename":self.allow_auto_rename,
+            "mute":self.mute_notification,
+            "strict_conflict":self.allow_strict_conflict
+        });
+        if !self.property_groups.is_empty() {
+            arg["property_groups"] = json!(self.property_groups);
+        }
+        arg
+    }
+}
+
 #[derive(Clone)]
 pub struct MoveCopyOption {
     allow_shared_folder: bool,
@@ -137,6 +265,12 @@ pub struct MoveCopyOptionBuilder {
     allow_ownership_transfer: bool,
 }
 
+impl Default for MoveCopyOptionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MoveCopyOptionBuilder {
     ///new will return an option with follow value
     ///sheared_folder:"true", autorename:"false", ownership_transfer:"false"
@@ -173,9 +307,256 @@ impl MoveCopyOptionBuilder {
 }
 
 #[derive(Clone)]
+pub struct ListFolderOption {
+    recursive: bool,
+    include_deleted: bool,
+    limit: Option<u32>,
+    include_property_groups: Vec<String>,
+}
+pub struct ListFolderOptionBuilder {
+    recursive: bool,
+    include_deleted: bool,
+    limit: Option<u32>,
+    include_property_groups: Vec<String>,
+}
+
+impl Default for ListFolderOptionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ListFolderOptionBuilder {
+    ///new will return an option with follow value
+    ///recursive:"false", include_deleted:"false", limit:none, no property groups
+    pub fn new() -> Self {
+        Self {
+            recursive: false,
+            include_deleted: false,
+            limit: None,
+            include_property_groups: Vec::new(),
+        }
+    }
+
+    pub fn recursive(&mut self) -> &mut ListFolderOptionBuilder {
+        self.recursive = true;
+        self
+    }
+
+    pub fn include_deleted(&mut self) -> &mut ListFolderOptionBuilder {
+        self.include_deleted = true;
+        self
+    }
+
+    ///the maximum number of entries per page, dropbox may return fewer
+    pub fn set_limit(&mut self, limit: u32) -> &mut ListFolderOptionBuilder {
+        self.limit = Some(limit);
+        self
+    }
+
+    ///return the property groups of these templates with each file
+    pub fn include_property_groups(
+        &mut self,
+        template_ids: &[&str],
+    ) -> &mut ListFolderOptionBuilder {
+        self.include_property_groups = template_ids.iter().map(|id| id.to_string()).collect();
+        self
+    }
+
+    pub fn build(&self) -> ListFolderOption {
+        ListFolderOption {
+            recursive: self.recursive,
+            include_deleted: self.include_deleted,
+            limit: self.limit,
+            include_property_groups: self.include_property_groups.clone(),
+        }
+    }
+}
+
+impl ListFolderOption {
+    fn to_arg(&self, path: &str) -> serde_json::Value {
+        let mut arg = json!({
+            "path": path,
+            "recursive": self.recursive,
+            "include_deleted": self.include_deleted
+        });
+        if let Some(limit) = self.limit {
+            arg["limit"] = json!(limit);
+        }
+        if !self.include_property_groups.is_empty() {
+            arg["include_property_groups"] =
+                file_properties::template_filter(&self.include_property_groups);
+        }
+        arg
+    }
+}
+
+#[derive(Clone)]
+pub struct MetadataOption {
+    include_deleted: bool,
+    include_property_groups: Vec<String>,
+}
+pub struct MetadataOptionBuilder {
+    include_deleted: bool,
+    include_property_groups: Vec<String>,
+}
+
+impl Default for MetadataOptionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataOptionBuilder {
+    ///new will return an option with follow value
+    ///include_deleted:"false", no property groups
+    pub fn new() -> Self {
+        Self {
+            include_deleted: false,
+            include_property_groups: Vec::new(),
+        }
+    }
+
+    pub fn include_deleted(&mut self) -> &mut MetadataOptionBuilder {
+        self.include_deleted = true;
+        self
+    }
+
+    ///return the property groups of these templates with the file
+    pub fn include_property_groups(&mut self, template_ids: &[&str]) -> &mut MetadataOptionBuilder {
+        self.include_property_groups = template_ids.iter().map(|id| id.to_string()).collect();
+        self
+    }
+
+    pub fn build(&self) -> MetadataOption {
+        MetadataOption {
+            include_deleted: self.include_deleted,
+            include_property_groups: self.include_property_groups.clone(),
+        }
+    }
+}
+
+impl MetadataOption {
+    fn to_arg(&self, path: &str) -> serde_json::Value {
+        let mut arg = json!({
+            "path": path,
+            "include_deleted": self.include_deleted
+        });
+        if !self.include_property_groups.is_empty() {
+            arg["include_property_groups"] =
+                file_properties::template_filter(&self.include_property_groups);
+        }
+        arg
+    }
+}
+
+#[derive(Clone)]
+pub struct SearchOption {
+    path: Option<String>,
+    max_results: Option<u32>,
+    filename_only: bool,
+    include_property_groups: Vec<String>,
+}
+pub struct SearchOptionBuilder {
+    path: Option<String>,
+    max_results: Option<u32>,
+    filename_only: bool,
+    include_property_groups: Vec<String>,
+}
+
+impl Default for SearchOptionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SearchOptionBuilder {
+    ///new will return an option with follow value
+    ///path:none(whole dropbox), max_results:none, filename_only:"false", no property groups
+    pub fn new() -> Self {
+        Self {
+            path: None,
+            max_results: None,
+            filename_only: false,
+            include_property_groups: Vec::new(),
+        }
+    }
+
+    ///only search below this folder
+    pub fn set_path(&mut self, path: &str) -> &mut SearchOptionBuilder {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    pub fn set_max_results(&mut self, max_results: u32) -> &mut SearchOptionBuilder {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    pub fn filename_only(&mut self) -> &mut SearchOptionBuilder {
+        self.filename_only = true;
+        self
+    }
+
+    ///return the property groups of these templates with each match
+    pub fn include_property_groups(&mut self, template_ids: &[&str]) -> &mut SearchOptionBuilder {
+        self.include_property_groups = template_ids.iter().map(|id| id.to_string()).collect();
+        self
+    }
+
+    pub fn build(&self) -> SearchOption {
+        SearchOption {
+            path: self.path.clone(),
+            max_results: self.max_results,
+            filename_only: self.filename_only,
+            include_property_groups: self.include_property_groups.clone(),
+        }
+    }
+}
+
+impl SearchOption {
+    fn to_arg(&self, query: &str) -> serde_json::Value {
+        let mut options = json!({ "filename_only": self.filename_only });
+        if let Some(ref path) = self.path {
+            options["path"] = json!(path);
+        }
+        if let Some(max_results) = self.max_results {
+            options["max_results"] = json!(max_results);
+        }
+        let mut arg = json!({ "query": query, "options": options });
+        if !self.include_property_groups.is_empty() {
+            arg["include_property_groups"] =
+                file_properties::template_filter(&self.include_property_groups);
+        }
+        arg
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 ///Update will receive rev for the Update.0
 pub enum UploadMode {
     Add,
     Overwrite,
     Update(String),
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+///the namespace paths are resolved in, sent as `Dropbox-API-Path-Root`
+///Root.0 and NamespaceId.0 are namespace ids, e.g. `RootInfo::root_namespace_id`
+pub enum PathRoot {
+    Home,
+    Root(String),
+    NamespaceId(String),
+}
+
+impl PathRoot {
+    fn header_value(&self) -> String {
+        match self {
+            PathRoot::Home => json!({ ".tag": "home" }),
+            PathRoot::Root(id) => json!({ ".tag": "root", "root": id }),
+            PathRoot::NamespaceId(id) => json!({ ".tag": "namespace_id", "namespace_id": id }),
+        }
+        .to_string()
+    }
+}
@@ -0,0 +1,99 @@
+//metrics of the `metrics` feature, recorded through the `metrics` facade
+//install any exporter, e.g. `metrics-exporter-prometheus`, to collect them
+//every metric has an `endpoint` label, e.g. `/2/files/upload`
+use crate::DropboxError;
+use metrics::{counter, describe_counter, describe_histogram, histogram, Unit};
+use std::time::Duration;
+
+///requests sent, also the ones which got no response
+pub const REQUESTS_TOTAL: &str = "dropbox_requests_total";
+///failed requests, the `error` label is the `DropboxError` variant, e.g. `DbxPathError`
+pub const ERRORS_TOTAL: &str = "dropbox_errors_total";
+///seconds from sending the request until the response headers arrived
+pub const REQUEST_DURATION_SECONDS: &str = "dropbox_request_duration_seconds";
+///bytes of request bodies
+pub const BYTES_SENT_TOTAL: &str = "dropbox_bytes_sent_total";
+///bytes of response bodies, taken from the `Content-Length` header when the response arrives
+///chunked responses have no length and are not counted, so this can undercount downloads
+pub const BYTES_RECEIVED_TOTAL: &str = "dropbox_bytes_received_total";
+
+///register the descriptions and units of the metrics with the installed recorder
+pub fn describe() {
+    describe_counter!(REQUESTS_TOTAL, "requests sent to the dropbox api");
+    describe_counter!(ERRORS_TOTAL, "failed dropbox api requests by error");
+    describe_histogram!(
+        REQUEST_DURATION_SECONDS,
+        Unit::Seconds,
+        "latency of dropbox api requests"
+    );
+    describe_counter!(
+        BYTES_SENT_TOTAL,
+        Unit::Bytes,
+        "bytes sent to the dropbox api"
+    );
+    describe_counter!(
+        BYTES_RECEIVED_TOTAL,
+        Unit::Bytes,
+        "bytes received from the dropbox api per Content-Length, responses without it are not counted"
+    );
+}
+
+pub(crate) fn record_request(endpoint: &str, request_bytes: usize) {
+    counter!(REQUESTS_TOTAL, "endpoint" => endpoint.to_string()).increment(1);
+    counter!(BYTES_SENT_TOTAL, "endpoint" => endpoint.to_string()).increment(request_bytes as u64);
+}
+
+pub(crate) fn record_response(endpoint: &str, response_bytes: Option<u64>, duration: Duration) {
+    histogram!(REQUEST_DURATION_SECONDS, "endpoint" => endpoint.to_string()).record(duration);
+    if let Some(bytes) = response_bytes {
+        counter!(BYTES_RECEIVED_TOTAL, "endpoint" => endpoint.to_string()).increment(bytes);
+    }
+}
+
+///a request which did not get a response
+pub(crate) fn record_failure(endpoint: &str, error: &DropboxError, duration: Duration) {
+    histogram!(REQUEST_DURATION_SECONDS, "endpoint" => endpoint.to_string()).record(duration);
+    record_error(endpoint, error);
+}
+
+pub(crate) fn record_error(endpoint: &str, error: &DropboxError) {
+    counter!(
+        ERRORS_TOTAL,
+        "endpoint" => endpoint.to_string(),
+        "error" => error_name(error)
+    )
+    .increment(1);
+}
+
+///name of the `DropboxError` variant
+pub(crate) fn error_name(error: &DropboxError) -> &'static str {
+    match error {
+        #[cfg(feature = "non-blocking")]
+        DropboxError::NonBlockingRequestError(_) => "NonBlockingRequestError",
+        #[cfg(feature = "blocking")]
+        DropboxError::BlockingRequestError(_) => "BlockingRequestError",
+        DropboxError::DbxUserCheckError(_) => "DbxUserCheckError",
+        DropboxError::DbxPathError(_) => "DbxPathError",
+        DropboxError::DbxExistedError(_) => "DbxExistedError",
+        DropboxError::DbxInvalidTokenError(_) => "DbxInvalidTokenError",
+        DropboxError::DbxInvalidAppError(_) => "DbxInvalidAppError",
+        DropboxError::DbxFromLookUpError(_) => "DbxFromLookUpError",
+        DropboxError::DbxRequestLimitsError(_) => "DbxRequestLimitsError",
+        DropboxError::DbxAccessError(_) => "DbxAccessError",
+        DropboxError::DbxInvalidRootError(_) => "DbxInvalidRootError",
+        DropboxError::DbxConflictError(_) => "DbxConflictError",
+        DropboxError::DbxIncorrectOffsetError(_) => "DbxIncorrectOffsetError",
+        DropboxError::DbxServerError(_) => "DbxServerError",
+        DropboxError::OtherError(_) => "OtherError",
+        DropboxError::InvalidPathError(_) => "InvalidPathError",
+        DropboxError::ContentHashMismatchError(_) => "ContentHashMismatchError",
+        #[cfg(feature = "blocking")]
+        DropboxError::BodyParseError(_) => "BodyParseError",
+        DropboxError::JsonParseError(_) => "JsonParseError",
+        DropboxError::DbxSaveUrlError(_) => "DbxSaveUrlError",
+        DropboxError::DbxShareFolderError(_) => "DbxShareFolderError",
+        DropboxError::DbxLockFileError(_) => "DbxLockFileError",
+        #[cfg(feature = "extract")]
+        DropboxError::ZipExtractError(_) => "ZipExtractError",
+    }
+}
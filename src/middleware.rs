@@ -0,0 +1,152 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+///hooks around every request of a client, e.g. to add headers, measure latency or audit calls
+///`before_send` runs in the order the middlewares were added, `after_receive` in reverse order
+///both clients call them the same way, header names are lowercase and the `Authorization`
+///header is never visible
+pub trait Middleware: Send + Sync {
+    fn before_send(&self, _request: &mut RequestParts) {}
+
+    ///called for every response, also for error statuses, but not when no response arrived
+    fn after_receive(&self, _response: &ResponseParts) {}
+}
+
+///the request about to be sent
+#[derive(Debug, Clone)]
+pub struct RequestParts {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    added: Vec<(String, String)>,
+}
+
+impl RequestParts {
+    pub(crate) fn new<'a>(
+        endpoint: &str,
+        headers: impl Iterator<Item = (&'a str, &'a str)>,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            headers: visible_headers(headers),
+            added: Vec::new(),
+        }
+    }
+
+    ///path of the endpoint, e.g. `/2/files/upload`
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    ///add the header or replace its value
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        let name = name.to_ascii_lowercase();
+        self.headers.retain(|(n, _)| *n != name);
+        self.headers.push((name.clone(), value.to_string()));
+        self.added.push((name, value.to_string()));
+    }
+
+    ///headers set by the middlewares, applied to the request in this order
+    pub(crate) fn added_headers(&self) -> &[(String, String)] {
+        &self.added
+    }
+}
+
+///the response of a request
+#[derive(Debug, Clone)]
+pub struct ResponseParts {
+    endpoint: String,
+    status: u16,
+    headers: Vec<(String, String)>,
+    elapsed: Duration,
+}
+
+impl ResponseParts {
+    pub(crate) fn new<'a>(
+        endpoint: &str,
+        status: u16,
+        headers: impl Iterator<Item = (&'a str, &'a str)>,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            endpoint: endpoint.to_string(),
+            status,
+            headers: visible_headers(headers),
+            elapsed,
+        }
+    }
+
+    ///path of the endpoint, e.g. `/2/files/upload`
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    ///time from sending the request until the response headers arrived
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+fn visible_headers<'a>(headers: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<(String, String)> {
+    headers
+        .filter(|(name, _)| !name.eq_ignore_ascii_case("authorization"))
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.to_string()))
+        .collect()
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+///middlewares of a client
+#[derive(Clone, Default)]
+pub(crate) struct MiddlewareChain(Vec<Arc<dyn Middleware>>);
+
+impl MiddlewareChain {
+    pub(crate) fn push(&mut self, middleware: Arc<dyn Middleware>) {
+        self.0.push(middleware);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn before_send(&self, request: &mut RequestParts) {
+        for middleware in &self.0 {
+            middleware.before_send(request);
+        }
+    }
+
+    pub(crate) fn after_receive(&self, response: &ResponseParts) {
+        for middleware in self.0.iter().rev() {
+            middleware.after_receive(response);
+        }
+    }
+}
+
+impl fmt::Debug for MiddlewareChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MiddlewareChain({} middlewares)", self.0.len())
+    }
+}
@@ -0,0 +1,188 @@
+#![cfg_attr(feature = "blocking", allow(clippy::result_large_err))]
+use crate::{DropboxError, DropboxResult};
+use serde::Serialize;
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+///a dropbox path checked and normalized before it is sent
+///accepted forms are `/a/b` (the root is the empty string), `id:<id>`, `id:<id>/a/b`, `rev:<rev>`,
+///`ns:<namespace id>` and `ns:<namespace id>/a/b`
+///a missing leading slash is added, repeated and trailing slashes and `.` segments are dropped,
+///`..` segments, control characters and the names dropbox does not store, e.g. `desktop.ini`,
+///are rejected, anything else is left to the server
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(into = "String")]
+pub struct DropboxPath(String);
+
+impl DropboxPath {
+    ///the root of the user's dropbox or of the `PathRoot` set on the client
+    pub fn root() -> Self {
+        DropboxPath(String::new())
+    }
+
+    pub fn new(path: &str) -> DropboxResult<Self> {
+        if let Some(rev) = path.strip_prefix("rev:") {
+            if rev.is_empty() || rev.contains('/') {
+                return Err(invalid(path, "a revision can not be empty or contain `/`"));
+            }
+            check_chars(path, rev)?;
+            return Ok(DropboxPath(path.to_string()));
+        }
+        for prefix in ["id:", "ns:"] {
+            if let Some(rest) = path.strip_prefix(prefix) {
+                let (base, sub_path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+                if base.is_empty() {
+                    return Err(invalid(path, "missing id after the prefix"));
+                }
+                if prefix == "ns:" && !base.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(invalid(path, "a namespace id is a number"));
+                }
+                check_chars(path, base)?;
+                let sub_path = normalize(path, sub_path)?;
+                return Ok(DropboxPath(format!("{}{}{}", prefix, base, sub_path)));
+            }
+        }
+        Ok(DropboxPath(normalize(path, path)?))
+    }
+
+    ///append one or more `/` separated segments
+    ///a `rev:` path can not be joined
+    pub fn join(&self, segments: &str) -> DropboxResult<Self> {
+        if self.is_rev() {
+            return Err(invalid(&self.0, "a revision can not be joined"));
+        }
+        let joined = format!("{}/{}", self.0, segments);
+        let segments = normalize(&joined, segments)?;
+        Ok(DropboxPath(format!("{}{}", self.0, segments)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_id(&self) -> bool {
+        self.0.starts_with("id:")
+    }
+
+    pub fn is_rev(&self) -> bool {
+        self.0.starts_with("rev:")
+    }
+
+    pub fn is_namespace(&self) -> bool {
+        self.0.starts_with("ns:")
+    }
+}
+
+///`/` joined segments with a leading slash, empty for the root
+fn normalize(path: &str, segments: &str) -> DropboxResult<String> {
+    let mut normalized = String::with_capacity(segments.len() + 1);
+    for segment in segments.split('/') {
+        match segment {
+            "" | "." => continue,
+            ".." => return Err(invalid(path, "`..` is not supported")),
+            _ => {
+                check_chars(path, segment)?;
+                check_name(path, segment)?;
+                normalized.push('/');
+                normalized.push_str(segment);
+            }
+        }
+    }
+    Ok(normalized)
+}
+
+fn check_chars(path: &str, part: &str) -> DropboxResult<()> {
+    match part.chars().any(char::is_control) {
+        true => Err(invalid(path, "control characters are not allowed")),
+        false => Ok(()),
+    }
+}
+
+///names dropbox refuses to store with a `disallowed_name` error
+const DISALLOWED_NAMES: [&str; 5] = [
+    "desktop.ini",
+    "thumbs.db",
+    ".ds_store",
+    ".dropbox",
+    ".dropbox.attr",
+];
+
+///whether dropbox refuses to store a file or folder with this name, e.g. `.DS_Store`
+pub(crate) fn is_disallowed_name(name: &str) -> bool {
+    DISALLOWED_NAMES
+        .iter()
+        .any(|disallowed| name.eq_ignore_ascii_case(disallowed))
+}
+
+fn check_name(path: &str, name: &str) -> DropboxResult<()> {
+    match is_disallowed_name(name) {
+        true => Err(invalid(
+            path,
+            "dropbox does not store files or folders with this name",
+        )),
+        false => Ok(()),
+    }
+}
+
+fn invalid(path: &str, reason: &str) -> DropboxError {
+    DropboxError::InvalidPathError(format!("{:?}: {}", path, reason))
+}
+
+impl fmt::Display for DropboxPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for DropboxPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for DropboxPath {
+    type Err = DropboxError;
+
+    fn from_str(path: &str) -> DropboxResult<Self> {
+        Self::new(path)
+    }
+}
+
+impl TryFrom<&str> for DropboxPath {
+    type Error = DropboxError;
+
+    fn try_from(path: &str) -> DropboxResult<Self> {
+        Self::new(path)
+    }
+}
+
+impl TryFrom<&String> for DropboxPath {
+    type Error = DropboxError;
+
+    fn try_from(path: &String) -> DropboxResult<Self> {
+        Self::new(path)
+    }
+}
+
+impl TryFrom<String> for DropboxPath {
+    type Error = DropboxError;
+
+    fn try_from(path: String) -> DropboxResult<Self> {
+        Self::new(&path)
+    }
+}
+
+impl From<&DropboxPath> for DropboxPath {
+    fn from(path: &DropboxPath) -> Self {
+        path.clone()
+    }
+}
+
+impl From<DropboxPath> for String {
+    fn from(path: DropboxPath) -> Self {
+        path.0
+    }
+}
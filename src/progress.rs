@@ -0,0 +1,25 @@
+///progress of an upload or download, reported after every chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    ///bytes transferred so far
+    pub transferred: u64,
+    ///bytes of the whole transfer, `None` if it is not known upfront
+    pub total: Option<u64>,
+    ///bytes of the chunk which was just transferred
+    pub chunk: u64,
+}
+
+///receives the progress of transfers, e.g. to drive a progress bar
+///closures taking a `Progress` are observers too
+pub trait ProgressObserver: Send + Sync {
+    fn on_progress(&self, progress: Progress);
+}
+
+impl<F> ProgressObserver for F
+where
+    F: Fn(Progress) + Send + Sync,
+{
+    fn on_progress(&self, progress: Progress) {
+        self(progress)
+    }
+}
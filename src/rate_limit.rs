@@ -0,0 +1,181 @@
+use crate::DbxRequestLimitsErrorSummary;
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+///the endpoints are limited separately, content endpoints upload and download file bodies,
+///rpc endpoints take and return json
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointClass {
+    Rpc,
+    Content,
+}
+
+///pause used when neither the header nor the body of a 429 response tell how long to wait
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+///lowest configurable rate, one request in 100 seconds
+const MIN_RATE: f64 = 0.01;
+///a 429 response halves the rate, but never below `rate / MAX_SLOWDOWN`
+const MAX_SLOWDOWN: f64 = 16.0;
+///every successful response gives back this part of the configured rate
+const RECOVERY_STEP: f64 = 0.05;
+
+///token bucket limiter for the requests of one or more clients
+///share it with `Arc` and `set_rate_limiter` to keep many clients and workers under one budget
+///a 429 response pauses the bucket of its endpoint class for `Retry-After`, or the `retry_after`
+///of its body when the header is missing, and halves its rate,
+///successful responses slowly restore the configured rate
+#[derive(Debug)]
+pub struct RateLimiter {
+    rpc: Mutex<Bucket>,
+    content: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    fn bucket(&self, class: EndpointClass) -> &Mutex<Bucket> {
+        match class {
+            EndpointClass::Rpc => &self.rpc,
+            EndpointClass::Content => &self.content,
+        }
+    }
+
+    ///current requests per second of the endpoint class, lower than configured after a 429
+    pub fn current_rate(&self, class: EndpointClass) -> f64 {
+        self.bucket(class).lock().unwrap().rate
+    }
+
+    ///take a token and return how long the caller has to wait before sending
+    pub(crate) fn reserve(&self, class: EndpointClass) -> Duration {
+        self.bucket(class).lock().unwrap().reserve(Instant::now())
+    }
+
+    ///adjust the rate to the status of a response
+    pub(crate) fn on_response(
+        &self,
+        class: EndpointClass,
+        status: u16,
+        retry_after: Option<Duration>,
+    ) {
+        let mut bucket = self.bucket(class).lock().unwrap();
+        match status {
+            429 => bucket.slow_down(Instant::now(), retry_after.unwrap_or(DEFAULT_RETRY_AFTER)),
+            200..=299 => bucket.recover(),
+            _ => {}
+        }
+    }
+}
+
+///seconds of a `Retry-After` header
+pub(crate) fn parse_retry_after(value: Option<&str>) -> Option<Duration> {
+    value?.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+///`retry_after` seconds of a 429 json body
+pub(crate) fn parse_retry_after_body(body: &str) -> Option<Duration> {
+    let error_summary = serde_json::from_str::<DbxRequestLimitsErrorSummary>(body).ok()?;
+    Some(Duration::from_secs(u64::from(
+        error_summary.error.retry_after,
+    )))
+}
+
+#[derive(Debug)]
+struct Bucket {
+    configured: f64,
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            configured: rate,
+            rate,
+            burst,
+            tokens: burst,
+            last: Instant::now(),
+        }
+    }
+
+    fn reserve(&mut self, now: Instant) -> Duration {
+        if now > self.last {
+            let refill = (now - self.last).as_secs_f64() * self.rate;
+            self.tokens = (self.tokens + refill).min(self.burst);
+            self.last = now;
+        }
+        self.tokens -= 1.0;
+        let mut wait = self.last.saturating_duration_since(now);
+        if self.tokens < 0.0 {
+            wait += Duration::from_secs_f64(-self.tokens / self.rate);
+        }
+        wait
+    }
+
+    fn slow_down(&mut self, now: Instant, retry_after: Duration) {
+        self.rate = (self.rate / 2.0).max(self.configured / MAX_SLOWDOWN);
+        //no tokens are refilled while paused, requests already waiting keep their place
+        let resume = now + retry_after;
+        if resume > self.last {
+            self.last = resume;
+            self.tokens = self.tokens.min(0.0);
+        }
+    }
+
+    fn recover(&mut self) {
+        self.rate = (self.rate + self.configured * RECOVERY_STEP).min(self.configured);
+    }
+}
+
+pub struct RateLimiterBuilder {
+    rpc: (f64, u32),
+    content: (f64, u32),
+}
+
+impl Default for RateLimiterBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateLimiterBuilder {
+    ///new will return an option with follow value
+    ///rpc: 10 requests per second with a burst of 10, content: 5 requests per second with a burst of 5
+    pub fn new() -> Self {
+        Self {
+            rpc: (10.0, 10),
+            content: (5.0, 5),
+        }
+    }
+
+    ///`requests_per_second` below 0.01, also zero or NaN, is raised to 0.01
+    ///`burst` is the number of requests sent without waiting
+    pub fn set_rpc_rate(
+        &mut self,
+        requests_per_second: f64,
+        burst: u32,
+    ) -> &mut RateLimiterBuilder {
+        self.rpc = (requests_per_second.max(MIN_RATE), burst);
+        self
+    }
+
+    ///`requests_per_second` below 0.01, also zero or NaN, is raised to 0.01
+    ///`burst` is the number of requests sent without waiting
+    pub fn set_content_rate(
+        &mut self,
+        requests_per_second: f64,
+        burst: u32,
+    ) -> &mut RateLimiterBuilder {
+        self.content = (requests_per_second.max(MIN_RATE), burst);
+        self
+    }
+
+    pub fn build(&self) -> RateLimiter {
+        RateLimiter {
+            rpc: Mutex::new(Bucket::new(self.rpc.0, self.rpc.1)),
+            content: Mutex::new(Bucket::new(self.content.0, self.content.1)),
+        }
+    }
+}
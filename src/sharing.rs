@@ -0,0 +1,515 @@
+use serde::{Deserialize, Serialize};
+
+///access level of a member to a shared folder or file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum AccessLevel {
+    Owner,
+    Editor,
+    Viewer,
+    ViewerNoComment,
+    Traverse,
+    NoAccess,
+    #[serde(other)]
+    Other,
+}
+
+///identify a member by email or by dropbox id (account id or team member id)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum MemberSelector {
+    Email { email: String },
+    DropboxId { dropbox_id: String },
+}
+
+impl MemberSelector {
+    pub fn email(email: &str) -> Self {
+        MemberSelector::Email {
+            email: email.to_string(),
+        }
+    }
+
+    pub fn dropbox_id(dropbox_id: &str) -> Self {
+        MemberSelector::DropboxId {
+            dropbox_id: dropbox_id.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AddMember {
+    pub member: MemberSelector,
+    pub access_level: AccessLevel,
+}
+
+impl AddMember {
+    pub fn new(member: MemberSelector, access_level: AccessLevel) -> Self {
+        Self {
+            member,
+            access_level,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum MemberPolicy {
+    Team,
+    Anyone,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum AclUpdatePolicy {
+    Owner,
+    Editors,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SharedLinkPolicy {
+    Anyone,
+    Team,
+    Members,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum ViewerInfoPolicy {
+    Enabled,
+    Disabled,
+    #[serde(other)]
+    Other,
+}
+
+///policies for /share_folder and /update_folder_policy, unset policies are left to dropbox
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FolderPolicyOption {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    member_policy: Option<MemberPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    acl_update_policy: Option<AclUpdatePolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shared_link_policy: Option<SharedLinkPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    viewer_info_policy: Option<ViewerInfoPolicy>,
+}
+
+#[derive(Default)]
+pub struct FolderPolicyOptionBuilder {
+    option: FolderPolicyOption,
+}
+
+impl FolderPolicyOptionBuilder {
+    ///new will return an option without any policy set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_member_policy(&mut self, policy: MemberPolicy) -> &mut FolderPolicyOptionBuilder {
+        self.option.member_policy = Some(policy);
+        self
+    }
+
+    pub fn set_acl_update_policy(
+        &mut self,
+        policy: AclUpdatePolicy,
+    ) -> &mut FolderPolicyOptionBuilder {
+        self.option.acl_update_policy = Some(policy);
+        self
+    }
+
+    pub fn set_shared_link_policy(
+        &mut self,
+        policy: SharedLinkPolicy,
+    ) -> &mut FolderPolicyOptionBuilder {
+        self.option.shared_link_policy = Some(policy);
+        self
+    }
+
+    pub fn set_viewer_info_policy(
+        &mut self,
+        policy: ViewerInfoPolicy,
+    ) -> &mut FolderPolicyOptionBuilder {
+        self.option.viewer_info_policy = Some(policy);
+        self
+    }
+
+    pub fn build(&self) -> FolderPolicyOption {
+        self.option.clone()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FolderPolicy {
+    pub acl_update_policy: AclUpdatePolicy,
+    pub shared_link_policy: SharedLinkPolicy,
+    pub member_policy: Option<MemberPolicy>,
+    pub resolved_member_policy: Option<MemberPolicy>,
+    pub viewer_info_policy: Option<ViewerInfoPolicy>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Team {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedFolderMetadata {
+    pub shared_folder_id: String,
+    pub name: String,
+    pub access_type: AccessLevel,
+    pub is_inside_team_folder: bool,
+    pub is_team_folder: bool,
+    pub policy: FolderPolicy,
+    pub preview_url: String,
+    pub time_invited: String,
+    pub path_lower: Option<String>,
+    pub path_display: Option<String>,
+    pub parent_shared_folder_id: Option<String>,
+    pub owner_team: Option<Team>,
+    pub owner_display_names: Option<Vec<String>>,
+}
+
+///result of /share_folder
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum ShareFolderLaunch {
+    AsyncJobId { async_job_id: String },
+    Complete(SharedFolderMetadata),
+}
+
+///result of /check_share_job_status
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
+pub enum ShareFolderJobStatus {
+    InProgress,
+    Complete(SharedFolderMetadata),
+    Failed { failed: ShareFolderError },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum ShareFolderError {
+    EmailUnverified,
+    BadPath {
+        bad_path: SharePathError,
+    },
+    TeamPolicyDisallowsMemberPolicy,
+    DisallowedSharedLinkPolicy,
+    NoPermission,
+    #[serde(other)]
+    Other,
+}
+
+///why a path can not be shared
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SharePathError {
+    IsFile,
+    InsideSharedFolder,
+    ContainsSharedFolder,
+    ContainsAppFolder,
+    ContainsTeamFolder,
+    IsAppFolder,
+    InsideAppFolder,
+    IsPublicFolder,
+    InsidePublicFolder,
+    ///the folder is shared already, its metadata is returned
+    AlreadyShared(Box<SharedFolderMetadata>),
+    InvalidPath,
+    IsOsxPackage,
+    InsideOsxPackage,
+    IsVault,
+    IsVaultLocked,
+    IsFamily,
+    #[serde(other)]
+    Other,
+}
+
+///result of sharing endpoints which may run asynchronously, e.g. /unshare_folder
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SharingJobLaunch {
+    AsyncJobId { async_job_id: String },
+    Complete,
+}
+
+///result of /check_job_status
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SharingJobStatus {
+    InProgress,
+    Complete,
+    Failed { failed: JobError },
+}
+
+///why an asynchronous sharing job failed, the variant is named after the job
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum JobError {
+    UnshareFolderError {
+        unshare_folder_error: UnshareFolderError,
+    },
+    RemoveFolderMemberError {
+        remove_folder_member_error: RemoveFolderMemberError,
+    },
+    RelinquishFolderMembershipError {
+        relinquish_folder_membership_error: RelinquishFolderMembershipError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SharedFolderAccessError {
+    InvalidId,
+    NotAMember,
+    InvalidMember,
+    EmailUnverified,
+    Unmounted,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum UnshareFolderError {
+    AccessError {
+        access_error: SharedFolderAccessError,
+    },
+    TeamFolder,
+    NoPermission,
+    TooManyFiles,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum RelinquishFolderMembershipError {
+    AccessError {
+        access_error: SharedFolderAccessError,
+    },
+    FolderOwner,
+    Mounted,
+    GroupAccess,
+    TeamFolder,
+    NoPermission,
+    NoExplicitAccess,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MemberAccessLevelResult {
+    pub access_level: Option<AccessLevel>,
+    pub warning: Option<String>,
+}
+
+///result of /check_remove_member_job_status
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum RemoveMemberJobStatus {
+    InProgress,
+    Complete(MemberAccessLevelResult),
+    Failed { failed: RemoveFolderMemberError },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum RemoveFolderMemberError {
+    AccessError {
+        access_error: SharedFolderAccessError,
+    },
+    MemberError {
+        member_error: SharedFolderMemberError,
+    },
+    NoExplicitAccess,
+    FolderOwner,
+    GroupAccess,
+    TeamFolder,
+    NoPermission,
+    TooManyFiles,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SharedFolderMemberError {
+    InvalidDropboxId,
+    NotAMember,
+    ///the member has access through a group or a parent folder
+    NoExplicitAccess(MemberAccessLevelResult),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserInfo {
+    pub account_id: String,
+    pub email: String,
+    pub display_name: String,
+    pub same_team: bool,
+    pub team_member_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UserMembershipInfo {
+    pub access_type: AccessLevel,
+    pub user: UserInfo,
+    #[serde(default)]
+    pub is_inherited: bool,
+    pub initials: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupInfo {
+    pub group_id: String,
+    pub group_name: String,
+    pub is_member: bool,
+    pub is_owner: bool,
+    pub same_team: bool,
+    pub member_count: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroupMembershipInfo {
+    pub access_type: AccessLevel,
+    pub group: GroupInfo,
+    #[serde(default)]
+    pub is_inherited: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum InviteeInfo {
+    Email {
+        email: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct InviteeMembershipInfo {
+    pub access_type: AccessLevel,
+    pub invitee: InviteeInfo,
+    pub user: Option<UserInfo>,
+    #[serde(default)]
+    pub is_inherited: bool,
+}
+
+///result of /list_folder_members and /list_folder_members/continue
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedFolderMembers {
+    pub users: Vec<UserMembershipInfo>,
+    pub groups: Vec<GroupMembershipInfo>,
+    pub invitees: Vec<InviteeMembershipInfo>,
+    pub cursor: Option<String>,
+}
+
+///result of /list_folders and /list_folders/continue
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListFoldersResult {
+    pub entries: Vec<SharedFolderMetadata>,
+    pub cursor: Option<String>,
+}
+
+///result of /list_file_members and /list_file_members/continue
+pub type SharedFileMembers = SharedFolderMembers;
+
+///result of /get_file_metadata
+#[derive(Debug, Clone, Deserialize)]
+pub struct SharedFileMetadata {
+    pub id: String,
+    pub name: String,
+    pub policy: FolderPolicy,
+    pub preview_url: String,
+    pub access_type: Option<AccessLevel>,
+    pub path_lower: Option<String>,
+    pub path_display: Option<String>,
+    pub parent_shared_folder_id: Option<String>,
+    pub owner_team: Option<Team>,
+    pub owner_display_names: Option<Vec<String>>,
+    pub time_invited: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum FileMemberActionIndividualResult {
+    ///the access level the member ended up with, if it is different from the requested one
+    Success {
+        success: Option<AccessLevel>,
+    },
+    MemberError {
+        member_error: FileMemberActionError,
+    },
+}
+
+///why a member of a shared file could not be added or removed
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum FileMemberActionError {
+    InvalidMember,
+    NoPermission,
+    AccessError {
+        access_error: SharingFileAccessError,
+    },
+    ///the member has access through a parent folder or a group, not to the file itself
+    NoExplicitAccess(MemberAccessLevelResult),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SharingFileAccessError {
+    NoPermission,
+    InvalidFile,
+    IsFolder,
+    InsidePublicFolder,
+    InsideOsxPackage,
+    #[serde(other)]
+    Other,
+}
+
+///per-member result of /add_file_member
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileMemberActionResult {
+    pub member: MemberSelector,
+    pub result: FileMemberActionIndividualResult,
+}
+
+///result of /remove_file_member_2
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum FileMemberRemoveActionResult {
+    Success(MemberAccessLevelResult),
+    MemberError {
+        member_error: FileMemberActionError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+///result of /create_shared_link_with_settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedLinkMetadata {
+    pub url: String,
+    pub name: String,
+    pub id: Option<String>,
+    pub path_lower: Option<String>,
+    pub expires: Option<String>,
+}
@@ -0,0 +1,533 @@
+#![allow(clippy::result_large_err)]
+use crate::{
+    client::DBXClient,
+    content_hash::ContentHasher,
+    files::Metadata,
+    path::{is_disallowed_name, DropboxPath},
+    DropboxError, DropboxResult, ListFolderOptionBuilder, UploadMode, UploadOptionBuilder,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    convert::TryInto,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+///a file as it was after the last successful sync
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncEntry {
+    ///path relative to the synced folders, `/` separated
+    pub path: String,
+    pub rev: String,
+    pub content_hash: String,
+    pub size: u64,
+    ///local modification time, seconds since the unix epoch
+    pub modified: u64,
+}
+
+///state persisted between runs, entries are keyed by the lowercased relative path
+///without a state a run can not tell deleted files from new ones, so nothing is deleted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncState {
+    pub entries: BTreeMap<String, SyncEntry>,
+}
+
+impl SyncState {
+    ///load the state file, a missing file gives an empty state
+    pub fn load(path: &Path) -> DropboxResult<Self> {
+        match fs::read(path) {
+            Ok(data) => Ok(serde_json::from_slice(&data)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    ///write the state file, through a temporary file so an interrupted run keeps the old state
+    pub fn save(&self, path: &Path) -> DropboxResult<()> {
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalFile {
+    pub path: String,
+    pub content_hash: String,
+    pub size: u64,
+    pub modified: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteFile {
+    pub path: String,
+    pub rev: String,
+    pub content_hash: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncAction {
+    ///upload the local file, `rev` is the remote revision it replaces
+    Upload {
+        path: String,
+        rev: Option<String>,
+    },
+    Download {
+        path: String,
+        rev: String,
+    },
+    DeleteLocal {
+        path: String,
+    },
+    ///delete the remote file if it is still at `rev`
+    DeleteRemote {
+        path: String,
+        rev: String,
+    },
+    ///both sides changed since the last sync, the file is left alone
+    Conflict {
+        path: String,
+    },
+}
+
+impl SyncAction {
+    pub fn path(&self) -> &str {
+        match self {
+            SyncAction::Upload { path, .. }
+            | SyncAction::Download { path, .. }
+            | SyncAction::DeleteLocal { path }
+            | SyncAction::DeleteRemote { path, .. }
+            | SyncAction::Conflict { path } => path,
+        }
+    }
+}
+
+impl fmt::Display for SyncAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncAction::Upload { path, .. } => write!(f, "upload {}", path),
+            SyncAction::Download { path, .. } => write!(f, "download {}", path),
+            SyncAction::DeleteLocal { path } => write!(f, "delete local {}", path),
+            SyncAction::DeleteRemote { path, .. } => write!(f, "delete remote {}", path),
+            SyncAction::Conflict { path } => write!(f, "conflict {}", path),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SyncOption {
+    dry_run: bool,
+    propagate_deletes: bool,
+    state_file: Option<PathBuf>,
+}
+pub struct SyncOptionBuilder {
+    dry_run: bool,
+    propagate_deletes: bool,
+    state_file: Option<PathBuf>,
+}
+
+impl Default for SyncOptionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SyncOptionBuilder {
+    ///new will return an option with follow value
+    ///dry_run:"false", propagate_deletes:"true", state_file:none
+    pub fn new() -> Self {
+        Self {
+            dry_run: false,
+            propagate_deletes: true,
+            state_file: None,
+        }
+    }
+
+    ///only plan the actions, nothing is transferred and the state file is not written
+    pub fn dry_run(&mut self) -> &mut SyncOptionBuilder {
+        self.dry_run = true;
+        self
+    }
+
+    ///restore files deleted on one side from the other side instead of deleting them
+    pub fn keep_deleted(&mut self) -> &mut SyncOptionBuilder {
+        self.propagate_deletes = false;
+        self
+    }
+
+    ///the state is loaded from and saved to this file, it is skipped if inside the local folder
+    pub fn set_state_file(&mut self, path: &Path) -> &mut SyncOptionBuilder {
+        self.state_file = Some(path.to_path_buf());
+        self
+    }
+
+    pub fn build(&self) -> SyncOption {
+        SyncOption {
+            dry_run: self.dry_run,
+            propagate_deletes: self.propagate_deletes,
+            state_file: self.state_file.clone(),
+        }
+    }
+}
+
+///result of `sync`, in a dry run `actions` are the planned actions and `failed` is empty
+#[derive(Debug)]
+pub struct SyncReport {
+    pub actions: Vec<SyncAction>,
+    pub failed: Vec<(SyncAction, DropboxError)>,
+    pub dry_run: bool,
+}
+
+///keep the dropbox folder `remote_root` and the local folder `local_root` in sync
+///only files are synced, empty folders are not created on either side
+///uploads use `UploadMode::Update` with the revision seen when planning, so a file changed
+///remotely in between fails with a conflict instead of being overwritten
+///`remote_root` must be a plain path, `id:`, `ns:` and `rev:` roots fail with `InvalidPathError`
+pub fn sync<P>(
+    client: &DBXClient,
+    local_root: &Path,
+    remote_root: P,
+    option: SyncOption,
+) -> DropboxResult<SyncReport>
+where
+    P: TryInto<DropboxPath>,
+    DropboxError: From<P::Error>,
+{
+    let remote_root = remote_root.try_into()?;
+    check_root(&remote_root)?;
+    let state = match option.state_file {
+        Some(ref path) => SyncState::load(path)?,
+        None => SyncState::default(),
+    };
+    let mut local = scan_local(local_root, &state)?;
+    if let Some(key) = option
+        .state_file
+        .as_deref()
+        .and_then(|path| relative_key(local_root, path))
+    {
+        local.remove(&key);
+    }
+    let remote = scan_remote_path(client, &remote_root)?;
+    let actions = plan(&local, &remote, &state, option.propagate_deletes);
+    if option.dry_run {
+        return Ok(SyncReport {
+            actions,
+            failed: Vec::new(),
+            dry_run: true,
+        });
+    }
+
+    let mut new_state = SyncState::default();
+    for (key, local_file) in &local {
+        match (remote.get(key), state.entries.get(key)) {
+            (Some(remote_file), _) if remote_file.content_hash == local_file.content_hash => {
+                new_state
+                    .entries
+                    .insert(key.clone(), synced_entry(local_file, &remote_file.rev));
+            }
+            (_, Some(entry)) => {
+                new_state.entries.insert(key.clone(), entry.clone());
+            }
+            _ => {}
+        }
+    }
+    for (key, entry) in &state.entries {
+        if remote.contains_key(key) && !new_state.entries.contains_key(key) {
+            new_state.entries.insert(key.clone(), entry.clone());
+        }
+    }
+
+    let mut failed = Vec::new();
+    for action in &actions {
+        let key = action.path().to_lowercase();
+        match execute(client, local_root, &remote_root, action, &local, &remote) {
+            Ok(Some(entry)) => {
+                new_state.entries.insert(key, entry);
+            }
+            Ok(None) => {
+                new_state.entries.remove(&key);
+            }
+            Err(e) => failed.push((action.clone(), e)),
+        }
+    }
+    if let Some(ref path) = option.state_file {
+        new_state.save(path)?;
+    }
+    Ok(SyncReport {
+        actions,
+        failed,
+        dry_run: false,
+    })
+}
+
+///compare both sides with the state of the last sync and decide what to do for every file
+pub fn plan(
+    local: &BTreeMap<String, LocalFile>,
+    remote: &BTreeMap<String, RemoteFile>,
+    state: &SyncState,
+    propagate_deletes: bool,
+) -> Vec<SyncAction> {
+    let keys: BTreeSet<&String> = local.keys().chain(remote.keys()).collect();
+    let mut actions = Vec::new();
+    for key in keys {
+        let base = state.entries.get(key).map(|entry| &entry.content_hash);
+        let action = match (local.get(key), remote.get(key)) {
+            (Some(l), Some(r)) if l.content_hash == r.content_hash => None,
+            (Some(l), Some(r)) => match base {
+                Some(b) if *b == l.content_hash => Some(SyncAction::Download {
+                    path: r.path.clone(),
+                    rev: r.rev.clone(),
+                }),
+                Some(b) if *b == r.content_hash => Some(SyncAction::Upload {
+                    path: l.path.clone(),
+                    rev: Some(r.rev.clone()),
+                }),
+                _ => Some(SyncAction::Conflict {
+                    path: l.path.clone(),
+                }),
+            },
+            (Some(l), None) => match base {
+                Some(b) if *b == l.content_hash && propagate_deletes => {
+                    Some(SyncAction::DeleteLocal {
+                        path: l.path.clone(),
+                    })
+                }
+                _ => Some(SyncAction::Upload {
+                    path: l.path.clone(),
+                    rev: None,
+                }),
+            },
+            (None, Some(r)) => match base {
+                Some(b) if *b == r.content_hash && propagate_deletes => {
+                    Some(SyncAction::DeleteRemote {
+                        path: r.path.clone(),
+                        rev: r.rev.clone(),
+                    })
+                }
+                _ => Some(SyncAction::Download {
+                    path: r.path.clone(),
+                    rev: r.rev.clone(),
+                }),
+            },
+            (None, None) => None,
+        };
+        actions.extend(action);
+    }
+    actions
+}
+
+///hash every file below `root`, files unchanged in size and modification time since the last
+///sync reuse the hash from `state`
+///symlinks, files with non UTF-8 names and names dropbox does not store, e.g. `.DS_Store`,
+///are skipped
+pub fn scan_local(root: &Path, state: &SyncState) -> DropboxResult<BTreeMap<String, LocalFile>> {
+    let mut files = BTreeMap::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    let mut dirs = vec![(root.to_path_buf(), String::new())];
+    while let Some((dir, prefix)) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let name = match entry.file_name().into_string() {
+                Ok(name) if !is_disallowed_name(&name) => name,
+                _ => continue,
+            };
+            let path = format!("{}{}", prefix, name);
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                dirs.push((entry.path(), format!("{}/", path)));
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            let size = metadata.len();
+            let modified = modified_secs(&metadata);
+            let key = path.to_lowercase();
+            let content_hash = match state.entries.get(&key) {
+                Some(e) if e.size == size && e.modified == modified => e.content_hash.clone(),
+                _ => {
+                    let mut hasher = ContentHasher::new();
+                    io::copy(&mut fs::File::open(entry.path())?, &mut hasher)?;
+                    hasher.finish()
+                }
+            };
+            files.insert(
+                key,
+                LocalFile {
+                    path,
+                    content_hash,
+                    size,
+                    modified,
+                },
+            );
+        }
+    }
+    Ok(files)
+}
+
+///list every file below `root` on dropbox, a missing folder is empty
+///`root` must be a plain path like the `remote_root` of `sync`
+pub fn scan_remote<P>(client: &DBXClient, root: P) -> DropboxResult<BTreeMap<String, RemoteFile>>
+where
+    P: TryInto<DropboxPath>,
+    DropboxError: From<P::Error>,
+{
+    let root = root.try_into()?;
+    check_root(&root)?;
+    scan_remote_path(client, &root)
+}
+
+///the relative paths are taken from `path_display`, so only a plain path works as the root
+fn check_root(root: &DropboxPath) -> DropboxResult<()> {
+    match root.is_id() || root.is_namespace() || root.is_rev() {
+        true => Err(DropboxError::InvalidPathError(format!(
+            "{:?}: the remote root must be a plain path",
+            root.as_str()
+        ))),
+        false => Ok(()),
+    }
+}
+
+fn scan_remote_path(
+    client: &DBXClient,
+    root: &DropboxPath,
+) -> DropboxResult<BTreeMap<String, RemoteFile>> {
+    let depth = root
+        .as_str()
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .count();
+    let option = ListFolderOptionBuilder::new().recursive().build();
+    let mut result = match client.list_folder(root.as_str(), option) {
+        Ok(result) => result,
+        Err(DropboxError::DbxPathError(ref e)) if e == "not_found" => return Ok(BTreeMap::new()),
+        Err(e) => return Err(e),
+    };
+    let mut files = BTreeMap::new();
+    loop {
+        for entry in result.entries {
+            let file = match entry {
+                Metadata::File(file) => file,
+                _ => continue,
+            };
+            let path_display = match file.path_display {
+                Some(path) => path,
+                None => continue,
+            };
+            let path: Vec<&str> = path_display
+                .split('/')
+                .filter(|part| !part.is_empty())
+                .skip(depth)
+                .collect();
+            let path = path.join("/");
+            files.insert(
+                path.to_lowercase(),
+                RemoteFile {
+                    path,
+                    rev: file.rev,
+                    content_hash: file.content_hash.unwrap_or_default(),
+                    size: file.size,
+                },
+            );
+        }
+        if !result.has_more {
+            break;
+        }
+        result = client.list_folder_continue(&result.cursor)?;
+    }
+    Ok(files)
+}
+
+///run a single action, return the new state entry or `None` if the file is gone
+fn execute(
+    client: &DBXClient,
+    local_root: &Path,
+    remote_root: &DropboxPath,
+    action: &SyncAction,
+    local: &BTreeMap<String, LocalFile>,
+    remote: &BTreeMap<String, RemoteFile>,
+) -> DropboxResult<Option<SyncEntry>> {
+    let key = action.path().to_lowercase();
+    let local_path = local_path(local_root, action.path());
+    let remote_path = remote_root.join(action.path())?;
+    match action {
+        SyncAction::Upload { rev, .. } => {
+            let mode = match rev {
+                Some(rev) => UploadMode::Update(rev.clone()),
+                None => UploadMode::Add,
+            };
+            let option = UploadOptionBuilder::new()
+                .disallow_auto_rename()
+                .set_upload_mode(mode)
+                .build();
+            let metadata = client.upload_file(fs::read(&local_path)?, &remote_path, option)?;
+            let local_file = &local[&key];
+            Ok(Some(synced_entry(local_file, &metadata.rev)))
+        }
+        SyncAction::Download { rev, .. } => {
+            let content = client.download(format!("rev:{}", rev))?;
+            if let Some(parent) = local_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&local_path, &content)?;
+            let remote_file = &remote[&key];
+            Ok(Some(SyncEntry {
+                path: remote_file.path.clone(),
+                rev: rev.clone(),
+                content_hash: remote_file.content_hash.clone(),
+                size: content.len() as u64,
+                modified: modified_secs(&fs::metadata(&local_path)?),
+            }))
+        }
+        SyncAction::DeleteLocal { .. } => {
+            fs::remove_file(&local_path)?;
+            Ok(None)
+        }
+        SyncAction::DeleteRemote { rev, .. } => {
+            client.delete(remote_path.as_str(), Some(rev))?;
+            Ok(None)
+        }
+        SyncAction::Conflict { path } => Err(DropboxError::DbxConflictError(format!(
+            "{} changed on both sides",
+            path
+        ))),
+    }
+}
+
+fn synced_entry(local_file: &LocalFile, rev: &str) -> SyncEntry {
+    SyncEntry {
+        path: local_file.path.clone(),
+        rev: rev.to_string(),
+        content_hash: local_file.content_hash.clone(),
+        size: local_file.size,
+        modified: local_file.modified,
+    }
+}
+
+fn local_path(root: &Path, relative: &str) -> PathBuf {
+    relative
+        .split('/')
+        .fold(root.to_path_buf(), |path, part| path.join(part))
+}
+
+fn relative_key(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Option<Vec<&str>> = relative.iter().map(|part| part.to_str()).collect();
+    Some(parts?.join("/").to_lowercase())
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,66 @@
+use crate::users::Name;
+use serde::Deserialize;
+
+///result of /team/get_info
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamInfo {
+    pub name: String,
+    pub team_id: String,
+    pub num_licensed_users: u32,
+    pub num_provisioned_users: u32,
+    pub num_used_licenses: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum TeamMemberStatus {
+    Active,
+    Invited,
+    Suspended,
+    Removed,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum TeamMembershipType {
+    Full,
+    Limited,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamMemberProfile {
+    pub team_member_id: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub status: TeamMemberStatus,
+    pub name: Name,
+    pub membership_type: TeamMembershipType,
+    pub account_id: Option<String>,
+    pub external_id: Option<String>,
+    pub joined_on: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamMemberRole {
+    pub role_id: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TeamMemberInfo {
+    pub profile: TeamMemberProfile,
+    pub roles: Option<Vec<TeamMemberRole>>,
+}
+
+///result of /team/members/list_v2 and /team/members/list/continue_v2
+#[derive(Debug, Clone, Deserialize)]
+pub struct MembersListResult {
+    pub members: Vec<TeamMemberInfo>,
+    pub cursor: String,
+    pub has_more: bool,
+}
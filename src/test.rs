@@ -7,6 +7,7 @@ mod tests {
         io::{Read, Write},
     };
 
+    #[cfg(feature = "non-blocking")]
     #[tokio::test]
     async fn test_user_check() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -16,6 +17,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "non-blocking")]
     #[tokio::test]
     async fn test_upload() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -29,6 +31,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "non-blocking")]
     #[tokio::test]
     async fn test_move() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -45,6 +48,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "non-blocking")]
     #[tokio::test]
     async fn test_copy() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -61,6 +65,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "non-blocking")]
     #[tokio::test]
     async fn test_download() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -72,6 +77,83 @@ mod tests {
         file.write_all(&bytes).unwrap();
     }
 
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_get_preview() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let res = client.get_preview("/test/profile.docx").await;
+        let preview = res.unwrap();
+        assert_eq!(preview.metadata.name, "profile.docx");
+        assert!(!preview.content.is_empty())
+    }
+
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_save_url() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let res = client
+            .save_url_and_wait(
+                "/test/robots.txt",
+                "https://www.dropbox.com/robots.txt",
+                std::time::Duration::from_secs(1),
+            )
+            .await;
+        assert_eq!(res.unwrap().name, "robots.txt")
+    }
+
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_share_file() {
+        use sharing::*;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let reviewer = MemberSelector::email("reviewer@example.com");
+        let res = client
+            .add_file_member(
+                "/profile.jpg",
+                std::slice::from_ref(&reviewer),
+                AccessLevel::Viewer,
+                true,
+            )
+            .await;
+        assert_eq!(res.unwrap()[0].member, reviewer);
+        let res = client
+            .update_file_member("/profile.jpg", &reviewer, AccessLevel::Editor)
+            .await;
+        assert!(res.is_ok());
+        let res = client.unshare_file("/profile.jpg").await;
+        assert!(res.is_ok())
+    }
+
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_upload_with_tags() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let mut file = File::open("./profile.jpg").unwrap();
+        let mut buf: Vec<u8> = Vec::new();
+        file.read_to_end(&mut buf).unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let option = UploadOptionBuilder::new().build();
+        let metadata = client
+            .upload_with_tags(buf, "/test/tagged.jpg", option, &["invoice", "customer_42"])
+            .await
+            .unwrap();
+        let path = metadata.path_display.unwrap();
+        let tags = client.get_tags(&[&path]).await.unwrap();
+        assert!(tags[0].tags.contains(&files::Tag::UserGeneratedTag {
+            tag_text: "invoice".to_string()
+        }));
+        let res = client.remove_tag(&path, "invoice").await;
+        assert!(res.is_ok())
+    }
+
+    #[cfg(feature = "blocking")]
     #[test]
     fn test_blocking_user_check() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -80,6 +162,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "blocking")]
     #[test]
     fn test_blocking_upload() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -92,6 +175,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "blocking")]
     #[test]
     fn test_blocking_move() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -105,6 +189,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "blocking")]
     #[test]
     fn test_blocking_copy() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -118,6 +203,7 @@ mod tests {
         assert!(res.is_ok())
     }
 
+    #[cfg(feature = "blocking")]
     #[test]
     fn test_blocking_download() {
         let token = env::var("DROPBOX_TOKEN").unwrap();
@@ -127,4 +213,1386 @@ mod tests {
         let mut file = File::create("new_profile.jpg").unwrap();
         file.write_all(&bytes).unwrap();
     }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_export() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let res = client.export("/test/profile.paper", Some("markdown"));
+        let exported = res.unwrap();
+        assert_eq!(exported.metadata.file_metadata.name, "profile.paper");
+        assert!(!exported.content.is_empty())
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_download_zip() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let mut stream = client.download_zip("/test").unwrap();
+        assert_eq!(stream.metadata.metadata.name, "test");
+        let mut bytes = Vec::new();
+        stream.reader.read_to_end(&mut bytes).unwrap();
+        assert!(!bytes.is_empty())
+    }
+
+    #[cfg(feature = "extract")]
+    #[test]
+    fn test_sanitize_entry_path() {
+        use std::path::PathBuf;
+        assert_eq!(
+            archive::sanitize_entry_path("test/profile.jpg"),
+            Some(PathBuf::from("test/profile.jpg"))
+        );
+        assert_eq!(
+            archive::sanitize_entry_path("./test//profile.jpg"),
+            Some(PathBuf::from("test/profile.jpg"))
+        );
+        assert_eq!(archive::sanitize_entry_path("../profile.jpg"), None);
+        assert_eq!(archive::sanitize_entry_path("test/../../profile.jpg"), None);
+        assert_eq!(archive::sanitize_entry_path("..\\profile.jpg"), None);
+        assert_eq!(archive::sanitize_entry_path("/etc/passwd"), None);
+        assert_eq!(archive::sanitize_entry_path(""), None);
+    }
+
+    #[cfg(feature = "extract")]
+    #[test]
+    fn test_extract_zip() {
+        use std::io::Cursor;
+        use zip::{write::FileOptions, ZipWriter};
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .add_directory("test/", FileOptions::default())
+            .unwrap();
+        writer
+            .start_file("test/profile.txt", FileOptions::default())
+            .unwrap();
+        writer.write_all(b"profile").unwrap();
+        let safe = writer.finish().unwrap();
+
+        let dest = env::temp_dir().join(format!("rust-dropbox-extract-{}", std::process::id()));
+        let extracted = archive::extract_zip(safe, &dest).unwrap();
+        assert_eq!(extracted, vec![dest.join("test/profile.txt")]);
+        assert_eq!(std::fs::read(&extracted[0]).unwrap(), b"profile");
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        writer
+            .start_file("../escaped.txt", FileOptions::default())
+            .unwrap();
+        writer.write_all(b"escaped").unwrap();
+        let unsafe_zip = writer.finish().unwrap();
+        assert!(archive::extract_zip(unsafe_zip, &dest).is_err());
+        assert!(!dest.join("../escaped.txt").exists());
+        std::fs::remove_dir_all(&dest).unwrap();
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_get_current_account() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let account = client.get_current_account().unwrap();
+        let same = client.get_account(&account.account_id).unwrap();
+        assert_eq!(account.email, same.email);
+        let usage = client.get_space_usage().unwrap();
+        assert!(usage.available().is_some())
+    }
+
+    #[test]
+    fn test_space_usage_available() {
+        let usage: users::SpaceUsage = serde_json::from_str(
+            r#"{"used":300,"allocation":{".tag":"individual","allocated":1000}}"#,
+        )
+        .unwrap();
+        assert_eq!(usage.available(), Some(700));
+        let usage: users::SpaceUsage = serde_json::from_str(
+            r#"{"used":300,"allocation":{".tag":"team","used":5000,"allocated":10000,
+            "user_within_team_space_allocated":1000,"user_within_team_space_used_cached":300,
+            "user_within_team_space_limit_type":{".tag":"stop_sync"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(usage.available(), Some(700));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_check_app() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let res = client.check_app("bad_key", "bad_secret", "ping");
+        assert!(matches!(res, Err(DropboxError::DbxInvalidAppError(_))))
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_basic_auth_value() {
+        assert_eq!(
+            client::basic_auth_value("Aladdin", "open sesame"),
+            "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="
+        );
+        assert_eq!(client::basic_auth_value("a", ""), "Basic YTo=");
+        assert_eq!(client::basic_auth_value("ab", ""), "Basic YWI6");
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_path_root() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let account = client.get_current_account().unwrap();
+        let root = PathRoot::Root(account.root_info.root_namespace_id().to_string());
+        let res = client.with_path_root(root).download("/profile.jpg");
+        assert!(res.is_ok());
+        let res = client
+            .with_path_root(PathRoot::Root("1".to_string()))
+            .download("/profile.jpg");
+        match res {
+            Err(DropboxError::DbxInvalidRootError(root_info)) => {
+                assert_eq!(&root_info, &account.root_info)
+            }
+            _ => panic!("expected invalid root error"),
+        }
+    }
+
+    #[test]
+    fn test_path_root_header_value() {
+        assert_eq!(PathRoot::Home.header_value(), r#"{".tag":"home"}"#);
+        assert_eq!(
+            PathRoot::Root("123".to_string()).header_value(),
+            r#"{".tag":"root","root":"123"}"#
+        );
+        assert_eq!(
+            PathRoot::NamespaceId("456".to_string()).header_value(),
+            r#"{".tag":"namespace_id","namespace_id":"456"}"#
+        );
+        let summary: DbxPathRootErrorSummary = serde_json::from_str(
+            r#"{"error_summary":"invalid_root/...","error":{".tag":"invalid_root",
+            "invalid_root":{".tag":"team","root_namespace_id":"7","home_namespace_id":"8",
+            "home_path":"/Alice"}}}"#,
+        )
+        .unwrap();
+        match summary.error {
+            PathRootError::InvalidRoot { invalid_root } => {
+                assert_eq!(invalid_root.root_namespace_id(), "7")
+            }
+            _ => panic!("expected invalid_root"),
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_team_as_member() {
+        let token = env::var("DROPBOX_TEAM_TOKEN").unwrap();
+        let client = client::DBXTeamClient::new(&token);
+        let info = client.get_info().unwrap();
+        assert!(info.num_provisioned_users > 0);
+        let members = client.list_members(Some(1), false).unwrap();
+        let member = &members.members[0].profile;
+        let account = client
+            .as_member(&member.team_member_id)
+            .get_current_account()
+            .unwrap();
+        assert_eq!(account.email, member.email)
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_share_folder() {
+        use sharing::*;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let option = FolderPolicyOptionBuilder::new()
+            .set_acl_update_policy(AclUpdatePolicy::Owner)
+            .build();
+        let metadata = client
+            .share_folder_and_wait("/test", option, std::time::Duration::from_secs(1))
+            .unwrap();
+        let members = [AddMember::new(
+            MemberSelector::email("reviewer@example.com"),
+            AccessLevel::Viewer,
+        )];
+        client
+            .add_folder_member(&metadata.shared_folder_id, &members, true)
+            .unwrap();
+        let listed = client
+            .list_folder_members(&metadata.shared_folder_id, None)
+            .unwrap();
+        assert!(listed
+            .invitees
+            .iter()
+            .any(|i| i.access_type == AccessLevel::Viewer));
+        let res = client.unshare_folder(&metadata.shared_folder_id, true);
+        assert!(res.is_ok())
+    }
+
+    #[test]
+    fn test_sharing_arg_serialize() {
+        use sharing::*;
+        let option = FolderPolicyOptionBuilder::new()
+            .set_member_policy(MemberPolicy::Team)
+            .set_shared_link_policy(SharedLinkPolicy::Members)
+            .build();
+        assert_eq!(
+            serde_json::to_string(&option).unwrap(),
+            r#"{"member_policy":{".tag":"team"},"shared_link_policy":{".tag":"members"}}"#
+        );
+        let member = AddMember::new(
+            MemberSelector::email("reviewer@example.com"),
+            AccessLevel::ViewerNoComment,
+        );
+        assert_eq!(
+            serde_json::to_string(&member).unwrap(),
+            r#"{"member":{".tag":"email","email":"reviewer@example.com"},"access_level":{".tag":"viewer_no_comment"}}"#
+        );
+    }
+
+    #[test]
+    fn test_file_member_result_deserialize() {
+        use sharing::*;
+        let results: Vec<FileMemberActionResult> = serde_json::from_str(
+            r#"[{"member":{".tag":"email","email":"a@example.com"},"result":{".tag":"success"}},
+            {"member":{".tag":"dropbox_id","dropbox_id":"dbid:1"},
+            "result":{".tag":"member_error","member_error":{".tag":"invalid_member"}}},
+            {"member":{".tag":"email","email":"b@example.com"},
+            "result":{".tag":"member_error","member_error":{".tag":"access_error",
+            "access_error":{".tag":"is_folder"}}}}]"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            results[0].result,
+            FileMemberActionIndividualResult::Success { success: None }
+        ));
+        assert_eq!(results[1].member, MemberSelector::dropbox_id("dbid:1"));
+        assert!(matches!(
+            results[1].result,
+            FileMemberActionIndividualResult::MemberError {
+                member_error: FileMemberActionError::InvalidMember
+            }
+        ));
+        assert!(matches!(
+            results[2].result,
+            FileMemberActionIndividualResult::MemberError {
+                member_error: FileMemberActionError::AccessError {
+                    access_error: SharingFileAccessError::IsFolder
+                }
+            }
+        ));
+        let removed: FileMemberRemoveActionResult = serde_json::from_str(
+            r#"{".tag":"member_error","member_error":{".tag":"no_explicit_access",
+            "access_level":{".tag":"editor"}}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            removed,
+            FileMemberRemoveActionResult::MemberError {
+                member_error: FileMemberActionError::NoExplicitAccess(MemberAccessLevelResult {
+                    access_level: Some(AccessLevel::Editor),
+                    ..
+                })
+            }
+        ));
+    }
+
+    #[test]
+    fn test_sharing_failure_deserialize() {
+        use sharing::*;
+        let status: SharingJobStatus = serde_json::from_str(
+            r#"{".tag":"failed","failed":{".tag":"unshare_folder_error",
+            "unshare_folder_error":{".tag":"access_error","access_error":{".tag":"not_a_member"}}}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            status,
+            SharingJobStatus::Failed {
+                failed: JobError::UnshareFolderError {
+                    unshare_folder_error: UnshareFolderError::AccessError {
+                        access_error: SharedFolderAccessError::NotAMember
+                    }
+                }
+            }
+        ));
+        let status: RemoveMemberJobStatus =
+            serde_json::from_str(r#"{".tag":"failed","failed":{".tag":"folder_owner"}}"#).unwrap();
+        assert!(matches!(
+            status,
+            RemoveMemberJobStatus::Failed {
+                failed: RemoveFolderMemberError::FolderOwner
+            }
+        ));
+        let error: ShareFolderError =
+            serde_json::from_str(r#"{".tag":"bad_path","bad_path":{".tag":"is_vault"}}"#).unwrap();
+        assert!(matches!(
+            error,
+            ShareFolderError::BadPath {
+                bad_path: SharePathError::IsVault
+            }
+        ));
+        //tags added to the api later fall back to `Other`
+        let error: ShareFolderError =
+            serde_json::from_str(r#"{".tag":"bad_path","bad_path":{".tag":"is_new"}}"#).unwrap();
+        assert!(matches!(
+            error,
+            ShareFolderError::BadPath {
+                bad_path: SharePathError::Other
+            }
+        ));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_file_request() {
+        use file_requests::*;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let option = FileRequestOptionBuilder::new()
+            .set_deadline(FileRequestDeadline::new(
+                "2030-01-01T00:00:00Z",
+                Some(GracePeriod::OneDay),
+            ))
+            .set_description("contracts")
+            .build();
+        let request = client
+            .create_file_request("contracts", "/test/requests", option)
+            .unwrap();
+        assert_eq!(request.destination.as_deref(), Some("/test/requests"));
+        let update = FileRequestUpdateBuilder::new()
+            .set_deadline(None)
+            .set_open(false)
+            .build();
+        let request = client.update_file_request(&request.id, update).unwrap();
+        assert!(!request.is_open);
+        assert!(request.deadline.is_none());
+        let deleted = client.delete_file_requests(&[&request.id]).unwrap();
+        assert_eq!(deleted.file_requests.len(), 1);
+        assert!(client.count_file_requests().is_ok())
+    }
+
+    #[test]
+    fn test_file_request_update_arg() {
+        use file_requests::*;
+        let update = FileRequestUpdateBuilder::new()
+            .set_title("contracts")
+            .set_deadline(Some(FileRequestDeadline::new(
+                "2030-01-01T00:00:00Z",
+                Some(GracePeriod::SevenDays),
+            )))
+            .build();
+        assert_eq!(
+            update.to_arg("oaCAVmEyrqYnkZX9955Y"),
+            serde_json::json!({
+                "id": "oaCAVmEyrqYnkZX9955Y",
+                "title": "contracts",
+                "deadline": {
+                    ".tag": "update",
+                    "deadline": "2030-01-01T00:00:00Z",
+                    "allow_late_uploads": { ".tag": "seven_days" }
+                }
+            })
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_lock_file() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        {
+            let guard = client.lock_file("/profile.jpg").unwrap();
+            assert!(matches!(
+                guard.lock().lock.content,
+                files::FileLockContent::SingleUser { .. }
+            ));
+        }
+        let entries = client.get_file_lock_batch(&["/profile.jpg"]).unwrap();
+        match &entries[0] {
+            files::LockFileResultEntry::Success(result) => assert!(matches!(
+                result.lock.content,
+                files::FileLockContent::Unlocked
+            )),
+            _ => panic!("expected lock info"),
+        }
+        let guard = client.lock_file("/profile.jpg").unwrap();
+        assert!(guard.release().is_ok())
+    }
+
+    #[test]
+    fn test_lock_file_result_deserialize() {
+        use files::*;
+        let result: LockFileBatchResult = serde_json::from_str(
+            r#"{"entries":[{".tag":"success","metadata":{".tag":"file","name":"profile.jpg",
+            "id":"id:a4ayc_80_OEAAAAAAAAAXw","client_modified":"2015-05-12T15:50:38Z",
+            "server_modified":"2015-05-12T15:50:38Z","rev":"a1c10ce0dd78","size":7212},
+            "lock":{"content":{".tag":"single_user","lock_holder_account_id":"dbid:1",
+            "created":"2015-05-12T15:50:38Z"}}},
+            {".tag":"failure","failure":{".tag":"lock_conflict",
+            "lock":{"content":{".tag":"single_user","lock_holder_account_id":"dbid:2",
+            "created":"2015-05-12T15:50:38Z"}}}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(result.entries.len(), 2);
+        let mut entries = result.entries.into_iter();
+        match single_lock_result(entries.next().into_iter().collect()) {
+            Ok(LockFileResult {
+                metadata: Metadata::File(file),
+                ..
+            }) => assert_eq!(file.name, "profile.jpg"),
+            _ => panic!("expected locked file"),
+        }
+        assert!(matches!(
+            single_lock_result(entries.collect()),
+            Err(DropboxError::DbxLockFileError(
+                LockFileError::LockConflict { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_tags_deserialize() {
+        let result: files::GetTagsResult = serde_json::from_str(
+            r#"{"paths_to_tags":[{"path":"/test/tagged.jpg","tags":[
+            {".tag":"user_generated_tag","tag_text":"invoice"}]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            result.paths_to_tags[0].tags,
+            vec![files::Tag::UserGeneratedTag {
+                tag_text: "invoice".to_string()
+            }]
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_file_properties() {
+        use file_properties::*;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let template_id = client
+            .add_property_template(
+                "jobs",
+                "job metadata",
+                &[PropertyFieldTemplate::string("customer_id", "customer")],
+            )
+            .unwrap();
+        let group = PropertyGroup::new(&template_id, vec![PropertyField::new("customer_id", "42")]);
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Overwrite)
+            .add_property_group(group.clone())
+            .build();
+        let res = client.upload(b"job".to_vec(), "/test/job.txt", option);
+        assert!(res.is_ok());
+        let option = MetadataOptionBuilder::new()
+            .include_property_groups(&[&template_id])
+            .build();
+        match client.get_metadata("/test/job.txt", option).unwrap() {
+            files::Metadata::File(file) => assert_eq!(file.property_groups, Some(vec![group])),
+            _ => panic!("expected file metadata"),
+        }
+        let found = client
+            .search_properties(&[PropertiesSearchQuery::field_name("customer_id", "42")])
+            .unwrap();
+        assert!(found.matches.iter().any(|m| m.path == "/test/job.txt"));
+        let res = client.remove_properties("/test/job.txt", &[&template_id]);
+        assert!(res.is_ok())
+    }
+
+    #[test]
+    fn test_option_to_arg() {
+        use file_properties::*;
+        let group = PropertyGroup::new(
+            "ptid:1a5n2i6d3OYEAAAAAAAAAYa",
+            vec![PropertyField::new("customer_id", "42")],
+        );
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Update("a1c10ce0dd78".to_string()))
+            .add_property_group(group)
+            .build();
+        assert_eq!(
+            option.to_arg("/test/job.txt"),
+            serde_json::json!({
+                "path": "/test/job.txt",
+                "mode": { ".tag": "update", "update": "a1c10ce0dd78" },
+                "autorename": true,
+                "mute": false,
+                "strict_conflict": false,
+                "property_groups": [{
+                    "template_id": "ptid:1a5n2i6d3OYEAAAAAAAAAYa",
+                    "fields": [{ "name": "customer_id", "value": "42" }]
+                }]
+            })
+        );
+        let option = ListFolderOptionBuilder::new()
+            .recursive()
+            .include_property_groups(&["ptid:1a5n2i6d3OYEAAAAAAAAAYa"])
+            .build();
+        assert_eq!(
+            option.to_arg("/test"),
+            serde_json::json!({
+                "path": "/test",
+                "recursive": true,
+                "include_deleted": false,
+                "include_property_groups": {
+                    ".tag": "filter_some",
+                    "filter_some": ["ptid:1a5n2i6d3OYEAAAAAAAAAYa"]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash::content_hash(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        let data = vec![7u8; 4 * 1024 * 1024 + 10];
+        let mut hasher = content_hash::ContentHasher::new();
+        for chunk in data.chunks(1000) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finish(), content_hash::content_hash(&data));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_sync_plan() {
+        use std::collections::BTreeMap;
+        use sync::{LocalFile, RemoteFile, SyncAction, SyncEntry, SyncState};
+
+        let local_file = |path: &str, hash: &str| LocalFile {
+            path: path.to_string(),
+            content_hash: hash.to_string(),
+            size: 1,
+            modified: 1,
+        };
+        let remote_file = |path: &str, hash: &str| RemoteFile {
+            path: path.to_string(),
+            rev: format!("rev-{}", hash),
+            content_hash: hash.to_string(),
+            size: 1,
+        };
+        let mut local = BTreeMap::new();
+        let mut remote = BTreeMap::new();
+        let mut state = SyncState::default();
+        for (path, l, r, base) in [
+            ("same.txt", Some("a"), Some("a"), Some("a")),
+            ("new_local.txt", Some("a"), None, None),
+            ("new_remote.txt", None, Some("a"), None),
+            ("local_changed.txt", Some("b"), Some("a"), Some("a")),
+            ("remote_changed.txt", Some("a"), Some("b"), Some("a")),
+            ("both_changed.txt", Some("b"), Some("c"), Some("a")),
+            ("remote_deleted.txt", Some("a"), None, Some("a")),
+            ("Dir/Local_Deleted.txt", None, Some("a"), Some("a")),
+        ] {
+            let key = path.to_lowercase();
+            if let Some(hash) = l {
+                local.insert(key.clone(), local_file(path, hash));
+            }
+            if let Some(hash) = r {
+                remote.insert(key.clone(), remote_file(path, hash));
+            }
+            if let Some(hash) = base {
+                let entry = SyncEntry {
+                    path: path.to_string(),
+                    rev: format!("rev-{}", hash),
+                    content_hash: hash.to_string(),
+                    size: 1,
+                    modified: 1,
+                };
+                state.entries.insert(key, entry);
+            }
+        }
+
+        let actions = sync::plan(&local, &remote, &state, true);
+        assert_eq!(
+            actions,
+            vec![
+                SyncAction::Conflict {
+                    path: "both_changed.txt".to_string()
+                },
+                SyncAction::DeleteRemote {
+                    path: "Dir/Local_Deleted.txt".to_string(),
+                    rev: "rev-a".to_string()
+                },
+                SyncAction::Upload {
+                    path: "local_changed.txt".to_string(),
+                    rev: Some("rev-a".to_string())
+                },
+                SyncAction::Upload {
+                    path: "new_local.txt".to_string(),
+                    rev: None
+                },
+                SyncAction::Download {
+                    path: "new_remote.txt".to_string(),
+                    rev: "rev-a".to_string()
+                },
+                SyncAction::Download {
+                    path: "remote_changed.txt".to_string(),
+                    rev: "rev-b".to_string()
+                },
+                SyncAction::DeleteLocal {
+                    path: "remote_deleted.txt".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            actions[1].to_string(),
+            "delete remote Dir/Local_Deleted.txt"
+        );
+
+        let actions = sync::plan(&local, &remote, &state, false);
+        assert!(actions.contains(&SyncAction::Download {
+            path: "Dir/Local_Deleted.txt".to_string(),
+            rev: "rev-a".to_string()
+        }));
+        assert!(actions.contains(&SyncAction::Upload {
+            path: "remote_deleted.txt".to_string(),
+            rev: None
+        }));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_sync_plan_skips_disallowed_names() {
+        use std::collections::BTreeMap;
+        use sync::{SyncAction, SyncState};
+
+        let root = env::temp_dir().join(format!("rust-dropbox-scan-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("a.txt"), b"a").unwrap();
+        std::fs::write(root.join(".DS_Store"), b"finder").unwrap();
+        std::fs::write(root.join("sub").join("Thumbs.db"), b"thumbs").unwrap();
+        std::fs::write(root.join("sub").join("desktop.ini"), b"ini").unwrap();
+        let local = sync::scan_local(&root, &SyncState::default());
+        let _ = std::fs::remove_dir_all(&root);
+        let local = local.unwrap();
+        assert_eq!(local.keys().collect::<Vec<_>>(), vec!["a.txt"]);
+        let actions = sync::plan(&local, &BTreeMap::new(), &SyncState::default(), true);
+        assert_eq!(
+            actions,
+            vec![SyncAction::Upload {
+                path: "a.txt".to_string(),
+                rev: None
+            }]
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_sync_remote_root() {
+        //the root is rejected before any request, so no token is needed
+        let client = client::DBXClient::new("token");
+        let local_root = env::temp_dir().join("rust-dropbox-sync-root");
+        for root in [
+            "id:a4ayc_80_OEAAAAAAAAAYa",
+            "ns:1234/sub",
+            "rev:a1c10ce0dd78",
+        ] {
+            let option = sync::SyncOptionBuilder::new().dry_run().build();
+            let res = sync::sync(&client, &local_root, root, option);
+            assert!(matches!(res, Err(DropboxError::InvalidPathError(_))));
+            let res = sync::scan_remote(&client, root);
+            assert!(matches!(res, Err(DropboxError::InvalidPathError(_))));
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_sync_dry_run() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let local_root = env::temp_dir().join(format!("rust-dropbox-sync-{}", std::process::id()));
+        std::fs::create_dir_all(&local_root).unwrap();
+        std::fs::write(local_root.join("sync.txt"), b"sync").unwrap();
+        let option = sync::SyncOptionBuilder::new().dry_run().build();
+        let report = sync::sync(&client, &local_root, "/test/sync", option).unwrap();
+        let _ = std::fs::remove_dir_all(&local_root);
+        assert!(report.dry_run);
+        assert!(report
+            .actions
+            .iter()
+            .any(|action| action.path() == "sync.txt"));
+    }
+
+    #[test]
+    fn test_dropbox_path() {
+        use path::DropboxPath;
+        use std::convert::TryFrom;
+
+        let normalize = |path: &str| DropboxPath::new(path).map(String::from).ok();
+        assert_eq!(normalize("/test/a.txt").as_deref(), Some("/test/a.txt"));
+        assert_eq!(normalize("test//a.txt/").as_deref(), Some("/test/a.txt"));
+        assert_eq!(normalize("/./test/").as_deref(), Some("/test"));
+        assert_eq!(normalize("/").as_deref(), Some(""));
+        assert_eq!(
+            normalize("id:a4ayc_80_OEAAAAAAAAAYa/sub/").as_deref(),
+            Some("id:a4ayc_80_OEAAAAAAAAAYa/sub")
+        );
+        assert_eq!(normalize("ns:1234").as_deref(), Some("ns:1234"));
+        assert_eq!(
+            normalize("rev:a1c10ce0dd78").as_deref(),
+            Some("rev:a1c10ce0dd78")
+        );
+        assert!(normalize("/test/../a.txt").is_none());
+        assert!(normalize("/test/a\nb.txt").is_none());
+        assert!(normalize("ns:team").is_none());
+        assert!(normalize("id:").is_none());
+        assert!(normalize("rev:a1c/b").is_none());
+        assert!(normalize("/test/Desktop.ini").is_none());
+        assert!(normalize("/.dropbox/a.txt").is_none());
+        assert!(normalize("id:a4ayc_80_OEAAAAAAAAAYa/thumbs.db").is_none());
+        assert_eq!(
+            normalize("/test/desktop.ini.txt").as_deref(),
+            Some("/test/desktop.ini.txt")
+        );
+
+        let dir = DropboxPath::try_from("/test").unwrap();
+        assert_eq!(dir.join("sub/a.txt").unwrap().as_str(), "/test/sub/a.txt");
+        assert_eq!(
+            DropboxPath::root().join("a.txt").unwrap().as_str(),
+            "/a.txt"
+        );
+        assert!(DropboxPath::new("rev:a1c10ce0dd78")
+            .unwrap()
+            .join("a.txt")
+            .is_err());
+        assert!(dir.join(".DS_Store").is_err());
+        assert!(matches!(
+            "/a/../b".parse::<DropboxPath>(),
+            Err(DropboxError::InvalidPathError(_))
+        ));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_download_dropbox_path() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let dir = path::DropboxPath::new("/test").unwrap();
+        let res = client.download(dir.join("test.txt").unwrap());
+        assert!(res.is_ok());
+        let res = client.download("/test/../test.txt");
+        assert!(matches!(res, Err(DropboxError::InvalidPathError(_))));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_begin_resumable_upload_invalid_path() {
+        //the path is rejected before any request, so no token is needed
+        let client = client::DBXClient::new("token");
+        let option = UploadOptionBuilder::new().build();
+        let res = client.begin_resumable_upload("/test/../resume.bin", option);
+        assert!(matches!(res, Err(DropboxError::InvalidPathError(_))));
+    }
+
+    #[test]
+    fn test_api_arg_header() {
+        let arg = serde_json::json!({ "path": "/テスト/😀 café.txt" });
+        let header = client::api_arg_header(&arg);
+        assert_eq!(
+            header,
+            r#"{"path":"/\u30c6\u30b9\u30c8/\ud83d\ude00 caf\u00e9.txt"}"#
+        );
+        assert!(header.is_ascii());
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&header).unwrap(),
+            arg
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_unicode_path_upload_download() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Overwrite)
+            .build();
+        let res = client.upload(b"unicode".to_vec(), "/test/テスト😀.txt", option);
+        assert!(res.is_ok());
+        let res = client.download("/test/テスト😀.txt");
+        assert_eq!(res.unwrap(), b"unicode".to_vec());
+    }
+
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_unicode_path_upload_download_async() {
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Overwrite)
+            .build();
+        let res = client
+            .upload(b"unicode".to_vec(), "/test/テスト😀_async.txt", option)
+            .await;
+        assert!(res.is_ok());
+        let res = client.download("/test/テスト😀_async.txt").await;
+        assert_eq!(res.unwrap(), b"unicode".to_vec());
+    }
+
+    #[test]
+    fn test_upload_session_finish_batch_entries() {
+        use files::{
+            UploadSessionCursor, UploadSessionFinishBatchResultEntry, UploadSessionFinishEntry,
+        };
+
+        let cursor = UploadSessionCursor {
+            session_id: "1234faaf0678bcde".to_string(),
+            offset: 11,
+        };
+        let option = UploadOptionBuilder::new().disallow_auto_rename().build();
+        let entry = UploadSessionFinishEntry::new(cursor, "/test/batch.txt", option);
+        assert_eq!(
+            entry.to_arg(),
+            serde_json::json!({
+                "cursor": { "session_id": "1234faaf0678bcde", "offset": 11 },
+                "commit": {
+                    "path": "/test/batch.txt",
+                    "mode": "add",
+                    "autorename": false,
+                    "mute": false,
+                    "strict_conflict": false
+                }
+            })
+        );
+        let entries: Vec<UploadSessionFinishBatchResultEntry> = serde_json::from_str(
+            r#"[
+                {".tag": "success", "name": "batch.txt", "id": "id:a4ayc_80_OEAAAAAAAAAXw",
+                 "client_modified": "2015-05-12T15:50:38Z", "server_modified": "2015-05-12T15:50:38Z",
+                 "rev": "a1c10ce0dd78", "size": 11, "path_display": "/test/batch.txt"},
+                {".tag": "failure", "failure": {".tag": "lookup_failed", "lookup_failed": {".tag": "not_closed"}}}
+            ]"#,
+        )
+        .unwrap();
+        assert!(
+            matches!(entries[0], UploadSessionFinishBatchResultEntry::Success(ref m) if m.size == 11)
+        );
+        assert!(matches!(
+            entries[1],
+            UploadSessionFinishBatchResultEntry::Failure { .. }
+        ));
+    }
+
+    #[test]
+    fn test_tagged_error() {
+        let error = |failure: serde_json::Value| client::tagged_error(&failure);
+        assert!(matches!(
+            error(serde_json::json!({".tag": "path", "path": {".tag": "conflict", "conflict": {".tag": "file"}}})),
+            DropboxError::DbxPathError(ref reason) if reason == "conflict"
+        ));
+        assert!(matches!(
+            error(
+                serde_json::json!({".tag": "lookup_failed", "lookup_failed": {".tag": "incorrect_offset", "correct_offset": 42}})
+            ),
+            DropboxError::DbxIncorrectOffsetError(42)
+        ));
+        assert!(matches!(
+            error(serde_json::json!({".tag": "lookup_failed", "lookup_failed": {".tag": "not_closed"}})),
+            DropboxError::DbxConflictError(ref summary) if summary == "lookup_failed/not_closed"
+        ));
+        assert!(matches!(
+            error(serde_json::json!({".tag": "too_many_write_operations"})),
+            DropboxError::DbxConflictError(_)
+        ));
+        assert!(matches!(
+            error(serde_json::json!("other")),
+            DropboxError::OtherError(_)
+        ));
+        assert!(matches!(
+            client::summary_error("to/conflict/folder/..".to_string()),
+            DropboxError::DbxExistedError(ref reason) if reason == "conflict"
+        ));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_transfer_manager() {
+        use transfer::{DownloadJob, TransferManager, UploadJob};
+
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let dir = env::temp_dir().join(format!("rust-dropbox-transfer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut uploads = Vec::new();
+        for i in 0..5 {
+            let local_path = dir.join(format!("{}.txt", i));
+            std::fs::write(&local_path, vec![b'a'; 100 * i]).unwrap();
+            let option = UploadOptionBuilder::new()
+                .set_upload_mode(UploadMode::Overwrite)
+                .build();
+            uploads.push(
+                UploadJob::new(&local_path, format!("/test/transfer/{}.txt", i), option).unwrap(),
+            );
+        }
+        let manager = TransferManager::new(client).with_chunk_size(128);
+        let results = manager.upload(uploads).unwrap();
+        assert!(results.iter().all(|result| result.result.is_ok()));
+        let downloads = (0..5)
+            .map(|i| {
+                DownloadJob::new(
+                    format!("/test/transfer/{}.txt", i),
+                    &dir.join(format!("{}.out", i)),
+                )
+                .unwrap()
+            })
+            .collect();
+        let results = manager.download(downloads);
+        let _ = std::fs::remove_dir_all(&dir);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(*result.result.as_ref().unwrap(), 100 * i as u64);
+        }
+    }
+
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_async_transfer_manager() {
+        use transfer::{AsyncTransferManager, UploadJob};
+
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        let dir = env::temp_dir().join(format!(
+            "rust-dropbox-async-transfer-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut uploads = Vec::new();
+        for i in 0..5 {
+            let local_path = dir.join(format!("{}.txt", i));
+            std::fs::write(&local_path, vec![b'a'; 100 * i]).unwrap();
+            let option = UploadOptionBuilder::new()
+                .set_upload_mode(UploadMode::Overwrite)
+                .build();
+            uploads.push(
+                UploadJob::new(
+                    &local_path,
+                    format!("/test/async_transfer/{}.txt", i),
+                    option,
+                )
+                .unwrap(),
+            );
+        }
+        let manager = AsyncTransferManager::new(client).with_parallelism(2);
+        let results = manager.upload(uploads).await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert!(results.iter().all(|result| result.result.is_ok()));
+    }
+
+    #[test]
+    fn test_progress_observer_closure() {
+        use progress::{Progress, ProgressObserver};
+        use std::sync::Mutex;
+
+        let reports = Mutex::new(Vec::new());
+        let observer = |progress: Progress| reports.lock().unwrap().push(progress.transferred);
+        let observer: &dyn ProgressObserver = &observer;
+        for transferred in [4, 8] {
+            observer.on_progress(Progress {
+                transferred,
+                total: Some(8),
+                chunk: 4,
+            });
+        }
+        assert_eq!(*reports.lock().unwrap(), vec![4, 8]);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_upload_download_with_progress() {
+        use progress::Progress;
+        use std::sync::Mutex;
+
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let file = vec![b'p'; 9 * 1024 * 1024];
+        let sent = Mutex::new(Vec::new());
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Overwrite)
+            .build();
+        let metadata = client
+            .upload_with_progress(
+                file.clone(),
+                "/test/progress.bin",
+                option,
+                &|p: Progress| sent.lock().unwrap().push(p),
+            )
+            .unwrap();
+        assert_eq!(metadata.size, file.len() as u64);
+        let sent = sent.into_inner().unwrap();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent.last().unwrap().transferred, file.len() as u64);
+
+        let received = Mutex::new(0u64);
+        let content = client
+            .download_with_progress("/test/progress.bin", &|p: Progress| {
+                *received.lock().unwrap() = p.transferred
+            })
+            .unwrap();
+        assert_eq!(content, file);
+        assert_eq!(*received.lock().unwrap(), file.len() as u64);
+    }
+
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_download_with_progress_async() {
+        use progress::Progress;
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        let received = AtomicU64::new(0);
+        let content = client
+            .download_with_progress("/test/test.txt", &|p: Progress| {
+                received.store(p.transferred, Ordering::Relaxed)
+            })
+            .await
+            .unwrap();
+        assert_eq!(received.load(Ordering::Relaxed), content.len() as u64);
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_download_range_and_resume() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let content: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Overwrite)
+            .build();
+        client
+            .upload(content.clone(), "/test/range.bin", option)
+            .unwrap();
+        let part = client.download_range("/test/range.bin", 100..200).unwrap();
+        assert_eq!(part, content[100..200].to_vec());
+
+        let local_path = env::temp_dir().join(format!("rust-dropbox-range-{}", std::process::id()));
+        std::fs::write(&local_path, &content[..4000]).unwrap();
+        let metadata = client
+            .download_to_file("/test/range.bin", &local_path)
+            .unwrap();
+        let downloaded = std::fs::read(&local_path).unwrap();
+        let _ = std::fs::remove_file(&local_path);
+        assert_eq!(metadata.size, 10_000);
+        assert_eq!(downloaded, content);
+    }
+
+    #[cfg(feature = "non-blocking")]
+    #[tokio::test]
+    async fn test_download_to_file_async() {
+        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::AsyncDBXClient::new(&token);
+        let expected = client.download("/test/test.txt").await.unwrap();
+        let part = client.download_range("/test/test.txt", 0..2).await.unwrap();
+        assert_eq!(part, expected[..2].to_vec());
+        let local_path =
+            env::temp_dir().join(format!("rust-dropbox-range-async-{}", std::process::id()));
+        std::fs::write(&local_path, &expected[..2]).unwrap();
+        client
+            .download_to_file("/test/test.txt", &local_path)
+            .await
+            .unwrap();
+        let downloaded = std::fs::read(&local_path).unwrap();
+        let _ = std::fs::remove_file(&local_path);
+        assert_eq!(downloaded, expected);
+    }
+
+    #[test]
+    fn test_incorrect_offset() {
+        let append = r#"{"error_summary": "incorrect_offset/..", "error": {".tag": "incorrect_offset", "correct_offset": 8388608}}"#;
+        assert_eq!(client::incorrect_offset(append), Some(8388608));
+        let finish = r#"{"error_summary": "lookup_failed/incorrect_offset/..", "error": {".tag": "lookup_failed", "lookup_failed": {".tag": "incorrect_offset", "correct_offset": 42}}}"#;
+        assert_eq!(client::incorrect_offset(finish), Some(42));
+        let other = r#"{"error_summary": "lookup_failed/not_found/..", "error": {".tag": "lookup_failed", "lookup_failed": {".tag": "not_found"}}}"#;
+        assert_eq!(client::incorrect_offset(other), None);
+    }
+
+    #[test]
+    fn test_realign_upload_session() {
+        let mut state = files::UploadSessionState {
+            cursor: files::UploadSessionCursor {
+                session_id: "session".to_string(),
+                offset: 100,
+            },
+            path: "/test/resume.bin".to_string(),
+            option: UploadOptionBuilder::new().build(),
+        };
+        client::realign(&mut state, 50, 1000, 1).unwrap();
+        assert_eq!(state.cursor.offset, 50);
+        //the same offset again or one past the end of the file would loop forever
+        assert!(client::realign(&mut state, 50, 1000, 2).is_err());
+        assert!(client::realign(&mut state, 1001, 1000, 2).is_err());
+        client::realign(&mut state, 80, 1000, 3).unwrap();
+        match client::realign(&mut state, 90, 1000, 4) {
+            Err(DropboxError::DbxIncorrectOffsetError(offset)) => assert_eq!(offset, 90),
+            _ => panic!("a chunk is realigned at most 3 times"),
+        }
+        assert_eq!(state.cursor.offset, 80);
+    }
+
+    #[test]
+    fn test_upload_session_state_roundtrip() {
+        let state = files::UploadSessionState {
+            cursor: files::UploadSessionCursor {
+                session_id: "session".to_string(),
+                offset: 1024,
+            },
+            path: "/test/resume.bin".to_string(),
+            option: UploadOptionBuilder::new()
+                .set_upload_mode(UploadMode::Update("rev".to_string()))
+                .build(),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        let restored: files::UploadSessionState = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.cursor.session_id, "session");
+        assert_eq!(restored.cursor.offset, 1024);
+        assert_eq!(restored.path, state.path);
+        assert_eq!(
+            serde_json::to_value(&restored.option).unwrap(),
+            serde_json::to_value(&state.option).unwrap()
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_resume_upload() {
+        use progress::Progress;
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let content: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let local_path =
+            env::temp_dir().join(format!("rust-dropbox-resume-{}", std::process::id()));
+        std::fs::write(&local_path, &content).unwrap();
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Overwrite)
+            .build();
+        let state = client
+            .begin_resumable_upload("/test/resume.bin", option)
+            .unwrap();
+        let saved = serde_json::to_string(&state).unwrap();
+        let mut state: files::UploadSessionState = serde_json::from_str(&saved).unwrap();
+        let reports = std::sync::Mutex::new(Vec::new());
+        let observer = |progress: Progress| reports.lock().unwrap().push(progress);
+        let metadata = client
+            .resume_upload(&mut state, &local_path, &mut |_| {}, &observer)
+            .unwrap();
+        let _ = std::fs::remove_file(&local_path);
+        assert_eq!(metadata.size, 10_000);
+        let last = *reports.lock().unwrap().last().unwrap();
+        assert_eq!((last.transferred, last.total), (10_000, Some(10_000)));
+    }
+
+    #[test]
+    fn test_rate_limiter_bucket() {
+        use rate_limit::EndpointClass;
+        use std::time::Duration;
+
+        let limiter = rate_limit::RateLimiterBuilder::new()
+            .set_rpc_rate(1.0, 2)
+            .set_content_rate(100.0, 1)
+            .build();
+        assert_eq!(limiter.reserve(EndpointClass::Rpc), Duration::ZERO);
+        assert_eq!(limiter.reserve(EndpointClass::Rpc), Duration::ZERO);
+        let wait = limiter.reserve(EndpointClass::Rpc);
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+        assert_eq!(limiter.reserve(EndpointClass::Content), Duration::ZERO);
+
+        limiter.on_response(EndpointClass::Content, 429, Some(Duration::from_secs(3)));
+        assert_eq!(limiter.current_rate(EndpointClass::Content), 50.0);
+        assert!(limiter.reserve(EndpointClass::Content) > Duration::from_millis(2900));
+        limiter.on_response(EndpointClass::Content, 200, None);
+        assert_eq!(limiter.current_rate(EndpointClass::Content), 55.0);
+        for _ in 0..20 {
+            limiter.on_response(EndpointClass::Content, 200, None);
+        }
+        assert_eq!(limiter.current_rate(EndpointClass::Content), 100.0);
+        assert_eq!(limiter.current_rate(EndpointClass::Rpc), 1.0);
+
+        assert_eq!(
+            rate_limit::parse_retry_after(Some(" 15")),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(rate_limit::parse_retry_after(Some("soon")), None);
+        let body = r#"{"error_summary": "too_many_requests/..", "error": {"reason": {".tag": "too_many_requests"}, "retry_after": 300}}"#;
+        assert_eq!(
+            rate_limit::parse_retry_after_body(body),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            rate_limit::parse_retry_after_body("too many requests"),
+            None
+        );
+
+        let limiter = rate_limit::RateLimiterBuilder::new()
+            .set_rpc_rate(0.0, 1)
+            .set_content_rate(f64::NAN, 1)
+            .build();
+        assert_eq!(limiter.current_rate(EndpointClass::Rpc), 0.01);
+        assert_eq!(limiter.current_rate(EndpointClass::Content), 0.01);
+        assert_eq!(limiter.reserve(EndpointClass::Rpc), Duration::ZERO);
+        assert!(limiter.reserve(EndpointClass::Rpc) > Duration::from_secs(99));
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_shared_rate_limiter() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let limiter = std::sync::Arc::new(
+            rate_limit::RateLimiterBuilder::new()
+                .set_rpc_rate(2.0, 1)
+                .build(),
+        );
+        let client = client::DBXClient::new(&token).with_rate_limiter(limiter.clone());
+        let other = client::DBXClient::new(&token).with_rate_limiter(limiter);
+        let start = std::time::Instant::now();
+        client.get_current_account().unwrap();
+        other.get_current_account().unwrap();
+        client.get_current_account().unwrap();
+        assert!(start.elapsed() >= std::time::Duration::from_millis(900));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_trace_path_arg() {
+        let arg = client::api_arg_header(&serde_json::json!({ "path": "/test/テスト.txt" }));
+        assert_eq!(
+            trace::path_arg(arg.as_bytes()).as_deref(),
+            Some("/test/テスト.txt")
+        );
+        let body = br#"{"from_path": "/a", "to_path": "/b"}"#;
+        assert_eq!(trace::path_arg(body).as_deref(), Some("/a"));
+        assert_eq!(trace::path_arg(b"binary \x00 body"), None);
+    }
+
+    #[test]
+    fn test_middleware_chain() {
+        use middleware::{Middleware, RequestParts, ResponseParts};
+        use std::sync::{Arc, Mutex};
+
+        struct Recorder(&'static str, Arc<Mutex<Vec<String>>>);
+        impl Middleware for Recorder {
+            fn before_send(&self, request: &mut RequestParts) {
+                assert_eq!(request.header("Authorization"), None);
+                request.set_header("X-Trace", self.0);
+                self.1.lock().unwrap().push(format!("before {}", self.0));
+            }
+            fn after_receive(&self, response: &ResponseParts) {
+                self.1
+                    .lock()
+                    .unwrap()
+                    .push(format!("after {} {}", self.0, response.status()));
+            }
+        }
+
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let mut chain = middleware::MiddlewareChain::default();
+        chain.push(Arc::new(Recorder("a", log.clone())));
+        chain.push(Arc::new(Recorder("b", log.clone())));
+        let headers = vec![
+            ("Authorization", "Bearer secret"),
+            ("Content-Type", "application/json"),
+        ];
+        let mut request = RequestParts::new("/2/files/delete_v2", headers.into_iter());
+        chain.before_send(&mut request);
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(request.header("x-trace"), Some("b"));
+        assert_eq!(request.headers().len(), 2);
+        assert_eq!(request.added_headers().len(), 2);
+        let response = ResponseParts::new(
+            "/2/files/delete_v2",
+            200,
+            std::iter::empty(),
+            std::time::Duration::ZERO,
+        );
+        chain.after_receive(&response);
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["before a", "before b", "after b 200", "after a 200"]
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_middleware_on_client() {
+        use middleware::{Middleware, ResponseParts};
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Default)]
+        struct Audit(Mutex<Vec<(String, u16)>>);
+        impl Middleware for Audit {
+            fn after_receive(&self, response: &ResponseParts) {
+                let entry = (response.endpoint().to_string(), response.status());
+                self.0.lock().unwrap().push(entry);
+            }
+        }
+
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let audit = Arc::new(Audit::default());
+        let client = client::DBXClient::new(&token).with_middleware(audit.clone());
+        client.get_current_account().unwrap();
+        assert_eq!(
+            *audit.0.lock().unwrap(),
+            vec![("/2/users/get_current_account".to_string(), 200)]
+        );
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_metric_error_name() {
+        assert_eq!(
+            metric::error_name(&DropboxError::DbxPathError("not_found".to_string())),
+            "DbxPathError"
+        );
+        assert_eq!(
+            metric::error_name(&DropboxError::DbxIncorrectOffsetError(0)),
+            "DbxIncorrectOffsetError"
+        );
+        //without an installed recorder the metrics are dropped
+        metric::describe();
+        metric::record_request("/2/files/upload", 10);
+        metric::record_error("/2/files/upload", &DropboxError::OtherError(String::new()));
+    }
+
+    #[test]
+    fn test_upload_mode_arg() {
+        let mode = |mode: UploadMode| {
+            UploadOptionBuilder::new()
+                .set_upload_mode(mode)
+                .build()
+                .to_arg("/test/job.txt")["mode"]
+                .clone()
+        };
+        assert_eq!(mode(UploadMode::Add), serde_json::json!("add"));
+        assert_eq!(mode(UploadMode::Overwrite), serde_json::json!("overwrite"));
+        //the update mode is a tagged union, the api rejects it as a json string
+        let update = mode(UploadMode::Update("a1c10ce0dd78".to_string()));
+        assert!(update.is_object());
+        assert_eq!(
+            update,
+            serde_json::json!({ ".tag": "update", "update": "a1c10ce0dd78" })
+        );
+    }
+
+    #[cfg(feature = "blocking")]
+    #[test]
+    fn test_blocking_upload_file_download_stream() {
+        let token = env::var("DROPBOX_TOKEN").unwrap();
+        let client = client::DBXClient::new(&token);
+        let option = UploadOptionBuilder::new()
+            .set_upload_mode(UploadMode::Overwrite)
+            .build();
+        let metadata = client
+            .upload_file(b"stream".to_vec(), "/test/stream.txt", option)
+            .unwrap();
+        assert_eq!(metadata.name, "stream.txt");
+        let mut stream = client.download_stream(metadata.id.as_str()).unwrap();
+        assert_eq!(stream.metadata.name, "stream.txt");
+        let mut content = Vec::new();
+        stream.reader.read_to_end(&mut content).unwrap();
+        assert_eq!(content, b"stream");
+    }
 }
@@ -0,0 +1,72 @@
+//spans and events of the `tracing` feature
+//only the endpoint, the path argument, sizes, status and request id are recorded,
+//headers are never recorded so the bearer token can not leak into the logs
+use crate::DropboxError;
+use std::time::Duration;
+use tracing::{field::Empty, Span};
+
+///span of one request, `api_arg` is the `Dropbox-API-Arg` header of content endpoints and
+///`body` the json body of rpc endpoints or the uploaded bytes
+///`retries` is how often the request was sent before, e.g. a chunk of `resume_upload` after a realignment
+pub(crate) fn request_span(
+    endpoint: &str,
+    api_arg: Option<&str>,
+    body: Option<&[u8]>,
+    retries: u32,
+) -> Span {
+    let path = match api_arg {
+        Some(arg) => path_arg(arg.as_bytes()),
+        None => body.and_then(path_arg),
+    };
+    tracing::info_span!(
+        "dropbox_request",
+        endpoint,
+        path = path.as_deref(),
+        status = Empty,
+        request_id = Empty,
+        duration_ms = Empty,
+        retries,
+        request_bytes = body.map_or(0, <[u8]>::len),
+        response_bytes = Empty,
+    )
+}
+
+///record the response on the span of the request
+pub(crate) fn record_response(
+    span: &Span,
+    status: u16,
+    request_id: Option<&str>,
+    response_bytes: Option<u64>,
+    duration: Duration,
+) {
+    span.record("status", status);
+    span.record("request_id", request_id);
+    span.record("response_bytes", response_bytes);
+    span.record("duration_ms", duration.as_millis() as u64);
+    tracing::debug!(parent: span, status, "dropbox response");
+}
+
+///record a request which did not get a response
+pub(crate) fn record_failure(span: &Span, error: &DropboxError, duration: Duration) {
+    span.record("duration_ms", duration.as_millis() as u64);
+    tracing::warn!(parent: span, error = ?error, "dropbox request failed");
+}
+
+///event for an error status turned into a `DropboxError` by `handle_*_request_response`
+pub(crate) fn error_response(
+    endpoint: &str,
+    status: u16,
+    request_id: Option<&str>,
+    error: &DropboxError,
+) {
+    tracing::warn!(endpoint, status, request_id, error = ?error, "dropbox error response");
+}
+
+///`path` or `from_path` of a json argument
+pub(crate) fn path_arg(arg: &[u8]) -> Option<String> {
+    let arg = serde_json::from_slice::<serde_json::Value>(arg).ok()?;
+    arg.get("path")
+        .or_else(|| arg.get("from_path"))?
+        .as_str()
+        .map(str::to_string)
+}
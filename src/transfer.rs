@@ -0,0 +1,556 @@
+#![cfg_attr(feature = "blocking", allow(clippy::result_large_err))]
+#[cfg(feature = "non-blocking")]
+use crate::client::AsyncDBXClient;
+#[cfg(feature = "blocking")]
+use crate::client::DBXClient;
+#[cfg(feature = "non-blocking")]
+use crate::io_error;
+use crate::{
+    client::{tagged_error, UPLOAD_CHUNK_SIZE},
+    files::{
+        FileMetadata, UploadSessionCursor, UploadSessionFinishBatchResultEntry,
+        UploadSessionFinishEntry,
+    },
+    path::DropboxPath,
+    progress::{Progress, ProgressObserver},
+    DropboxError, DropboxResult, UploadOption,
+};
+use std::{
+    convert::TryInto,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+///most entries /upload_session/finish_batch_v2 accepts at once
+const MAX_BATCH_SIZE: usize = 1000;
+///biggest chunk dropbox accepts in a single upload session call
+const MAX_CHUNK_SIZE: usize = 150 * 1024 * 1024;
+const DEFAULT_PARALLELISM: usize = 4;
+
+#[derive(Clone)]
+pub struct UploadJob {
+    pub local_path: PathBuf,
+    pub path: DropboxPath,
+    pub option: UploadOption,
+}
+
+impl UploadJob {
+    ///fail with `InvalidPathError` if `path` is not a valid dropbox path
+    pub fn new<P>(local_path: &Path, path: P, option: UploadOption) -> DropboxResult<Self>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        Ok(Self {
+            local_path: local_path.to_path_buf(),
+            path: path.try_into()?,
+            option,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct UploadResult {
+    pub local_path: PathBuf,
+    pub path: DropboxPath,
+    pub result: DropboxResult<FileMetadata>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub path: DropboxPath,
+    pub local_path: PathBuf,
+}
+
+impl DownloadJob {
+    ///fail with `InvalidPathError` if `path` is not a valid dropbox path
+    pub fn new<P>(path: P, local_path: &Path) -> DropboxResult<Self>
+    where
+        P: TryInto<DropboxPath>,
+        DropboxError: From<P::Error>,
+    {
+        Ok(Self {
+            path: path.try_into()?,
+            local_path: local_path.to_path_buf(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct DownloadResult {
+    pub path: DropboxPath,
+    pub local_path: PathBuf,
+    ///number of bytes written to `local_path`
+    pub result: DropboxResult<u64>,
+}
+
+///uploads and downloads many files with the `DBXClient` on a pool of threads
+///uploads are sent through upload sessions and committed together by
+///`upload_session_finish_batch`, which avoids lock contention on the namespace
+#[cfg(feature = "blocking")]
+#[derive(Clone)]
+pub struct TransferManager {
+    client: DBXClient,
+    parallelism: usize,
+    chunk_size: usize,
+    observer: Option<Arc<dyn ProgressObserver>>,
+}
+
+#[cfg(feature = "blocking")]
+impl TransferManager {
+    ///new will return a manager with follow value
+    ///parallelism:"4", chunk_size:"8 MB"
+    pub fn new(client: DBXClient) -> Self {
+        Self {
+            client,
+            parallelism: DEFAULT_PARALLELISM,
+            chunk_size: UPLOAD_CHUNK_SIZE,
+            observer: None,
+        }
+    }
+
+    ///number of files transferred at the same time, at least 1
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    ///bytes sent per upload session call, between 1 byte and 150 MB
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, MAX_CHUNK_SIZE);
+        self
+    }
+
+    ///report the progress over all files of an `upload` or `download` call
+    ///the total is only known for uploads
+    pub fn with_progress_observer(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    ///return a result per job, in the order of `jobs`
+    ///fail with the error of a failed `upload_session_finish_batch` call, the files of the
+    ///batches before it are committed
+    pub fn upload(&self, jobs: Vec<UploadJob>) -> DropboxResult<Vec<UploadResult>> {
+        let total = self.observer.as_ref().map(|_| total_size(&jobs));
+        let progress = SharedProgress::new(&self.observer, total);
+        let sessions = run_parallel(&jobs, self.parallelism, |job| {
+            upload_to_session(&self.client, &job.local_path, self.chunk_size, &progress)
+        });
+        let mut results: Vec<Option<DropboxResult<FileMetadata>>> = Vec::new();
+        let mut pending = Vec::new();
+        for (i, (job, session)) in jobs.iter().zip(sessions).enumerate() {
+            match session {
+                Ok(cursor) => {
+                    pending.push((i, finish_entry(job, cursor)));
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+        for batch in pending.chunks(MAX_BATCH_SIZE) {
+            let entries: Vec<UploadSessionFinishEntry> =
+                batch.iter().map(|(_, entry)| entry.clone()).collect();
+            let committed = self.client.upload_session_finish_batch(&entries)?;
+            for ((i, _), result) in batch.iter().zip(batch_results(committed, batch.len())) {
+                results[*i] = Some(result);
+            }
+        }
+        Ok(upload_results(jobs, results))
+    }
+
+    ///return a result per job, in the order of `jobs`
+    ///the files are streamed to disk, a failed download can leave a partial file behind
+    pub fn download(&self, jobs: Vec<DownloadJob>) -> Vec<DownloadResult> {
+        let progress = SharedProgress::new(&self.observer, None);
+        let results = run_parallel(&jobs, self.parallelism, |job| {
+            download_to_file(&self.client, job, &progress)
+        });
+        download_results(jobs, results)
+    }
+}
+
+///call `f` for every item on at most `parallelism` threads, results are in the order of `items`
+#[cfg(feature = "blocking")]
+fn run_parallel<T, R, F>(items: &[T], parallelism: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..parallelism.min(items.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let item = match items.get(i) {
+                    Some(item) => item,
+                    None => break,
+                };
+                let result = f(item);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every item is processed"))
+        .collect()
+}
+
+///upload the whole file into a closed session
+#[cfg(feature = "blocking")]
+fn upload_to_session(
+    client: &DBXClient,
+    local_path: &Path,
+    chunk_size: usize,
+    progress: &Option<SharedProgress>,
+) -> DropboxResult<UploadSessionCursor> {
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(local_path)?;
+    let size = file.metadata()?.len();
+    let mut cursor: Option<UploadSessionCursor> = None;
+    loop {
+        let mut chunk = Vec::with_capacity(chunk_size.min(size as usize));
+        (&mut file)
+            .take(chunk_size as u64)
+            .read_to_end(&mut chunk)?;
+        let offset = cursor.as_ref().map_or(0, |cursor| cursor.offset);
+        let close = chunk.len() < chunk_size || offset + chunk.len() as u64 >= size;
+        let sent = chunk.len() as u64;
+        let next = match cursor {
+            Some(ref cursor) => client.upload_session_append(cursor, chunk, close)?,
+            None => client.upload_session_start(chunk, close)?,
+        };
+        if let Some(progress) = progress {
+            progress.add(sent);
+        }
+        if close {
+            return Ok(next);
+        }
+        cursor = Some(next);
+    }
+}
+
+///stream the file of the job into its local path, return the number of written bytes
+#[cfg(feature = "blocking")]
+fn download_to_file(
+    client: &DBXClient,
+    job: &DownloadJob,
+    progress: &Option<SharedProgress>,
+) -> DropboxResult<u64> {
+    use std::io::{Read, Write};
+
+    let mut stream = client.download_stream(&job.path)?;
+    if let Some(parent) = job.local_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::File::create(&job.local_path)?;
+    let mut buf = vec![0; 64 * 1024];
+    let mut written = 0;
+    loop {
+        let read = stream.reader.read(&mut buf)?;
+        if read == 0 {
+            file.flush()?;
+            return Ok(written);
+        }
+        file.write_all(&buf[..read])?;
+        written += read as u64;
+        if let Some(progress) = progress {
+            progress.add(read as u64);
+        }
+    }
+}
+
+///uploads and downloads many files concurrently with the `AsyncDBXClient`
+///uploads are sent through upload sessions and committed together by
+///`upload_session_finish_batch`, which avoids lock contention on the namespace
+///the transfers are spawned as tasks, so the methods must be called inside a tokio runtime
+#[cfg(feature = "non-blocking")]
+#[derive(Clone)]
+pub struct AsyncTransferManager {
+    client: AsyncDBXClient,
+    parallelism: usize,
+    chunk_size: usize,
+    observer: Option<Arc<dyn ProgressObserver>>,
+}
+
+#[cfg(feature = "non-blocking")]
+impl AsyncTransferManager {
+    ///new will return a manager with follow value
+    ///parallelism:"4", chunk_size:"8 MB"
+    pub fn new(client: AsyncDBXClient) -> Self {
+        Self {
+            client,
+            parallelism: DEFAULT_PARALLELISM,
+            chunk_size: UPLOAD_CHUNK_SIZE,
+            observer: None,
+        }
+    }
+
+    ///number of files transferred at the same time, at least 1
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism.max(1);
+        self
+    }
+
+    ///bytes sent per upload session call, between 1 byte and 150 MB
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.clamp(1, MAX_CHUNK_SIZE);
+        self
+    }
+
+    ///report the progress over all files of an `upload` or `download` call
+    ///the total is only known for uploads
+    pub fn with_progress_observer(mut self, observer: Arc<dyn ProgressObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    ///return a result per job, in the order of `jobs`
+    ///fail with the error of a failed `upload_session_finish_batch` call, the files of the
+    ///batches before it are committed
+    pub async fn upload(&self, jobs: Vec<UploadJob>) -> DropboxResult<Vec<UploadResult>> {
+        let chunk_size = self.chunk_size;
+        let local_paths: Vec<PathBuf> = jobs.iter().map(|job| job.local_path.clone()).collect();
+        let total = self.observer.as_ref().map(|_| total_size(&jobs));
+        let progress = SharedProgress::new(&self.observer, total).map(Arc::new);
+        let sessions = run_concurrently(local_paths, self.parallelism, |local_path| {
+            let client = self.client.clone();
+            let progress = progress.clone();
+            async move {
+                upload_to_session_async(&client, &local_path, chunk_size, progress.as_deref()).await
+            }
+        })
+        .await;
+        let mut results: Vec<Option<DropboxResult<FileMetadata>>> = Vec::new();
+        let mut pending = Vec::new();
+        for (i, (job, session)) in jobs.iter().zip(sessions).enumerate() {
+            match session {
+                Ok(cursor) => {
+                    pending.push((i, finish_entry(job, cursor)));
+                    results.push(None);
+                }
+                Err(e) => results.push(Some(Err(e))),
+            }
+        }
+        for batch in pending.chunks(MAX_BATCH_SIZE) {
+            let entries: Vec<UploadSessionFinishEntry> =
+                batch.iter().map(|(_, entry)| entry.clone()).collect();
+            let committed = self.client.upload_session_finish_batch(&entries).await?;
+            for ((i, _), result) in batch.iter().zip(batch_results(committed, batch.len())) {
+                results[*i] = Some(result);
+            }
+        }
+        Ok(upload_results(jobs, results))
+    }
+
+    ///return a result per job, in the order of `jobs`
+    ///the files are streamed to disk, a failed download can leave a partial file behind
+    pub async fn download(&self, jobs: Vec<DownloadJob>) -> Vec<DownloadResult> {
+        let progress = SharedProgress::new(&self.observer, None).map(Arc::new);
+        let results = run_concurrently(jobs.clone(), self.parallelism, |job| {
+            let client = self.client.clone();
+            let progress = progress.clone();
+            async move { download_to_file_async(&client, &job, progress.as_deref()).await }
+        })
+        .await;
+        download_results(jobs, results)
+    }
+}
+
+///run the futures made by `f` as tasks, at most `parallelism` at a time
+///results are in the order of `items`
+#[cfg(feature = "non-blocking")]
+async fn run_concurrently<T, R, F, Fut>(items: Vec<T>, parallelism: usize, f: F) -> Vec<R>
+where
+    R: Send + 'static,
+    F: Fn(T) -> Fut,
+    Fut: std::future::Future<Output = R> + Send + 'static,
+{
+    let mut results: Vec<Option<R>> = (0..items.len()).map(|_| None).collect();
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut store = |joined: Result<(usize, R), tokio::task::JoinError>| match joined {
+        Ok((i, result)) => results[i] = Some(result),
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    };
+    for (i, item) in items.into_iter().enumerate() {
+        if tasks.len() >= parallelism {
+            if let Some(joined) = tasks.join_next().await {
+                store(joined);
+            }
+        }
+        let future = f(item);
+        tasks.spawn(async move { (i, future.await) });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        store(joined);
+    }
+    results
+        .into_iter()
+        .map(|result| result.expect("every item is processed"))
+        .collect()
+}
+
+///upload the whole file into a closed session
+#[cfg(feature = "non-blocking")]
+async fn upload_to_session_async(
+    client: &AsyncDBXClient,
+    local_path: &Path,
+    chunk_size: usize,
+    progress: Option<&SharedProgress>,
+) -> DropboxResult<UploadSessionCursor> {
+    use tokio::io::AsyncReadExt;
+
+    let mut file = tokio::fs::File::open(local_path).await.map_err(io_error)?;
+    let size = file.metadata().await.map_err(io_error)?.len();
+    let mut cursor: Option<UploadSessionCursor> = None;
+    loop {
+        let mut chunk = Vec::with_capacity(chunk_size.min(size as usize));
+        (&mut file)
+            .take(chunk_size as u64)
+            .read_to_end(&mut chunk)
+            .await
+            .map_err(io_error)?;
+        let offset = cursor.as_ref().map_or(0, |cursor| cursor.offset);
+        let close = chunk.len() < chunk_size || offset + chunk.len() as u64 >= size;
+        let sent = chunk.len() as u64;
+        let next = match cursor {
+            Some(ref cursor) => client.upload_session_append(cursor, chunk, close).await?,
+            None => client.upload_session_start(chunk, close).await?,
+        };
+        if let Some(progress) = progress {
+            progress.add(sent);
+        }
+        if close {
+            return Ok(next);
+        }
+        cursor = Some(next);
+    }
+}
+
+///stream the file of the job into its local path, return the number of written bytes
+#[cfg(feature = "non-blocking")]
+async fn download_to_file_async(
+    client: &AsyncDBXClient,
+    job: &DownloadJob,
+    progress: Option<&SharedProgress>,
+) -> DropboxResult<u64> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut stream = client.download_stream(&job.path).await?;
+    if let Some(parent) = job.local_path.parent() {
+        tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+    }
+    let mut file = tokio::fs::File::create(&job.local_path)
+        .await
+        .map_err(io_error)?;
+    let mut written = 0;
+    while let Some(chunk) = stream.chunk().await? {
+        file.write_all(&chunk).await.map_err(io_error)?;
+        written += chunk.len() as u64;
+        if let Some(progress) = progress {
+            progress.add(chunk.len() as u64);
+        }
+    }
+    file.flush().await.map_err(io_error)?;
+    Ok(written)
+}
+
+///sums up the chunks of all transfers of a manager call for its observer
+struct SharedProgress {
+    observer: Arc<dyn ProgressObserver>,
+    transferred: AtomicU64,
+    total: Option<u64>,
+}
+
+impl SharedProgress {
+    fn new(observer: &Option<Arc<dyn ProgressObserver>>, total: Option<u64>) -> Option<Self> {
+        observer.as_ref().map(|observer| SharedProgress {
+            observer: observer.clone(),
+            transferred: AtomicU64::new(0),
+            total,
+        })
+    }
+
+    fn add(&self, chunk: u64) {
+        let transferred = self.transferred.fetch_add(chunk, Ordering::Relaxed) + chunk;
+        self.observer.on_progress(Progress {
+            transferred,
+            total: self.total,
+            chunk,
+        });
+    }
+}
+
+///sum of the file sizes, files which can not be read count as empty
+fn total_size(jobs: &[UploadJob]) -> u64 {
+    jobs.iter()
+        .filter_map(|job| std::fs::metadata(&job.local_path).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn finish_entry(job: &UploadJob, cursor: UploadSessionCursor) -> UploadSessionFinishEntry {
+    UploadSessionFinishEntry::new(cursor, job.path.as_str(), job.option.clone())
+}
+
+///split the result of a finish_batch call into a result per entry
+fn batch_results(
+    entries: Vec<UploadSessionFinishBatchResultEntry>,
+    len: usize,
+) -> Vec<DropboxResult<FileMetadata>> {
+    if entries.len() != len {
+        let message = format!("expected {} batch results, got {}", len, entries.len());
+        return (0..len)
+            .map(|_| Err(DropboxError::OtherError(message.clone())))
+            .collect();
+    }
+    entries
+        .into_iter()
+        .map(|entry| match entry {
+            UploadSessionFinishBatchResultEntry::Success(metadata) => Ok(metadata),
+            UploadSessionFinishBatchResultEntry::Failure { failure } => Err(tagged_error(&failure)),
+        })
+        .collect()
+}
+
+fn upload_results(
+    jobs: Vec<UploadJob>,
+    results: Vec<Option<DropboxResult<FileMetadata>>>,
+) -> Vec<UploadResult> {
+    jobs.into_iter()
+        .zip(results)
+        .map(|(job, result)| UploadResult {
+            local_path: job.local_path,
+            path: job.path,
+            result: result.expect("every upload is committed or failed"),
+        })
+        .collect()
+}
+
+fn download_results(
+    jobs: Vec<DownloadJob>,
+    results: Vec<DropboxResult<u64>>,
+) -> Vec<DownloadResult> {
+    jobs.into_iter()
+        .zip(results)
+        .map(|(job, result)| DownloadResult {
+            path: job.path,
+            local_path: job.local_path,
+            result,
+        })
+        .collect()
+}
@@ -0,0 +1,144 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Name {
+    pub given_name: String,
+    pub surname: String,
+    pub familiar_name: String,
+    pub display_name: String,
+    pub abbreviated_name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum AccountType {
+    Basic,
+    Pro,
+    Business,
+}
+
+///root namespace of the account, the team space root for team members with team spaces
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum RootInfo {
+    Team {
+        root_namespace_id: String,
+        home_namespace_id: String,
+        home_path: String,
+    },
+    User {
+        root_namespace_id: String,
+        home_namespace_id: String,
+    },
+}
+
+impl RootInfo {
+    pub fn root_namespace_id(&self) -> &str {
+        match self {
+            RootInfo::Team {
+                root_namespace_id, ..
+            }
+            | RootInfo::User {
+                root_namespace_id, ..
+            } => root_namespace_id,
+        }
+    }
+
+    pub fn home_namespace_id(&self) -> &str {
+        match self {
+            RootInfo::Team {
+                home_namespace_id, ..
+            }
+            | RootInfo::User {
+                home_namespace_id, ..
+            } => home_namespace_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FullTeam {
+    pub id: String,
+    pub name: String,
+}
+
+///result of /get_current_account
+#[derive(Debug, Clone, Deserialize)]
+pub struct FullAccount {
+    pub account_id: String,
+    pub name: Name,
+    pub email: String,
+    pub email_verified: bool,
+    pub disabled: bool,
+    pub locale: String,
+    pub referral_link: String,
+    pub is_paired: bool,
+    pub account_type: AccountType,
+    pub root_info: RootInfo,
+    pub profile_photo_url: Option<String>,
+    pub country: Option<String>,
+    pub team: Option<FullTeam>,
+    pub team_member_id: Option<String>,
+}
+
+///result of /get_account and /get_account_batch
+#[derive(Debug, Clone, Deserialize)]
+pub struct BasicAccount {
+    pub account_id: String,
+    pub name: Name,
+    pub email: String,
+    pub email_verified: bool,
+    pub disabled: bool,
+    pub is_teammate: bool,
+    pub profile_photo_url: Option<String>,
+    pub team_member_id: Option<String>,
+}
+
+///result of /get_space_usage, sizes are in bytes
+#[derive(Debug, Clone, Deserialize)]
+pub struct SpaceUsage {
+    pub used: u64,
+    pub allocation: SpaceAllocation,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = ".tag", rename_all = "snake_case")]
+pub enum SpaceAllocation {
+    Individual {
+        allocated: u64,
+    },
+    Team {
+        used: u64,
+        allocated: u64,
+        user_within_team_space_allocated: u64,
+        user_within_team_space_used_cached: u64,
+    },
+    #[serde(other)]
+    Other,
+}
+
+impl SpaceUsage {
+    ///space left for the user, for team accounts the per-member limit is considered if set
+    pub fn available(&self) -> Option<u64> {
+        match self.allocation {
+            SpaceAllocation::Individual { allocated } => Some(allocated.saturating_sub(self.used)),
+            SpaceAllocation::Team {
+                used,
+                allocated,
+                user_within_team_space_allocated,
+                user_within_team_space_used_cached,
+            } => {
+                let team_left = allocated.saturating_sub(used);
+                match user_within_team_space_allocated {
+                    0 => Some(team_left),
+                    limit => Some(
+                        limit
+                            .saturating_sub(user_within_team_space_used_cached)
+                            .min(team_left),
+                    ),
+                }
+            }
+            SpaceAllocation::Other => None,
+        }
+    }
+}